@@ -0,0 +1,136 @@
+extern crate criterion;
+extern crate rust_robbo;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_robbo::engine::{Board, LevelSet, Registry};
+use std::rc::Rc;
+
+/// Ticks simulated per iteration of the tick benchmarks
+const TICKS: u64 = 100;
+
+const PACKS: &[&str] = &["original", "forever"];
+
+fn read_pack(name: &str) -> String {
+    let path = format!("{}/www/data/levels/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path, err))
+}
+
+/// Single level pack with a `size` x `size` board, walled around, Robbo in the top left
+/// corner and `cell(x, y)` everywhere else
+fn synthetic_pack(size: usize, cell: impl Fn(usize, usize) -> char, additional: &[String]) -> String {
+    let mut rows = vec!["O".repeat(size)];
+    for y in 1..size - 1 {
+        let row: String = (1..size - 1)
+            .map(|x| if (x, y) == (1, 1) { 'R' } else { cell(x, y) })
+            .collect();
+        rows.push(format!("O{}O", row));
+    }
+    rows.push("O".repeat(size));
+    format!(
+        "[name]\nSynthetic\n[level]\n1\n[colour]\n000000\n[size]\n{}.{}\n[data]\n{}\n[additional]\n{}\n{}\n[end]\n",
+        size,
+        size,
+        rows.join("\n"),
+        additional.len(),
+        additional.join("\n")
+    )
+}
+
+/// Every free cell is a butterfly
+fn butterflies() -> String {
+    synthetic_pack(64, |x, y| if x < 3 && y < 3 { '.' } else { 'V' }, &[])
+}
+
+/// Rotating laser guns on every 4th cell of every 4th row
+fn lasers() -> String {
+    let is_gun = |x: usize, y: usize| x.is_multiple_of(4) && y.is_multiple_of(4);
+    let additional: Vec<String> = (0..64)
+        .flat_map(|y| (0..64).map(move |x| (x, y)))
+        .filter(|&(x, y)| is_gun(x, y) && x > 0 && y > 0 && x < 63 && y < 63)
+        .map(|(x, y)| format!("{}.{}.}}.{}.0.1.0.1.0", x, y, (x + y) / 4 % 4))
+        .collect();
+    synthetic_pack(64, |x, y| if is_gun(x, y) { '}' } else { '.' }, &additional)
+}
+
+/// A gun next to a board full of bombs, setting off a chain of explosions
+fn bombs() -> String {
+    let additional = vec![String::from("1.2.}.0.0.0.0.0.0")];
+    synthetic_pack(
+        64,
+        |x, y| match (x, y) {
+            (_, 1) => '.',
+            (1, 2) => '}',
+            _ => 'b',
+        },
+        &additional,
+    )
+}
+
+fn tick(board: &mut Board, ticks: u64) {
+    for _ in 0..ticks {
+        board.tick();
+    }
+}
+
+fn bench_tick(c: &mut Criterion, group_name: &str, level_set: &LevelSet, registry: &Rc<Registry>) {
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(TICKS));
+    for (index, level) in level_set.levels.iter().enumerate() {
+        group.bench_with_input(BenchmarkId::from_parameter(index + 1), level, |b, level| {
+            b.iter_batched_ref(
+                || Board::from(level, 0, level_set.rules, registry),
+                |board| tick(board, TICKS),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn packs(c: &mut Criterion) {
+    let registry = Rc::new(Registry::default());
+    for pack in PACKS {
+        let level_set = LevelSet::parse(&read_pack(pack)).unwrap();
+        bench_tick(c, &format!("tick/{}", pack), &level_set, &registry);
+    }
+}
+
+fn worst_cases(c: &mut Criterion) {
+    let registry = Rc::new(Registry::default());
+    let cases = [("butterflies", butterflies()), ("lasers", lasers()), ("bombs", bombs())];
+    for (name, data) in cases.iter() {
+        let level_set = LevelSet::parse(data).unwrap();
+        bench_tick(c, &format!("tick/{}", name), &level_set, &registry);
+    }
+}
+
+fn construction(c: &mut Criterion) {
+    let registry = Rc::new(Registry::default());
+    let mut group = c.benchmark_group("board_from");
+    for pack in PACKS {
+        let level_set = LevelSet::parse(&read_pack(pack)).unwrap();
+        group.throughput(Throughput::Elements(level_set.levels.len() as u64));
+        group.bench_function(*pack, |b| {
+            b.iter(|| {
+                for level in level_set.levels.iter() {
+                    criterion::black_box(Board::from(level, 0, level_set.rules, &registry));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("level_set_parse");
+    for pack in PACKS {
+        let data = read_pack(pack);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(*pack, |b| b.iter(|| LevelSet::parse(criterion::black_box(&data))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, packs, worst_cases, construction, parsing);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+//! Parses arbitrary text as a level pack, every level of an accepted pack is built and ticked
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use rust_robbo::engine::{Board, LevelSet, Registry};
+use std::rc::Rc;
+
+const TICKS: usize = 16;
+
+fuzz_target!(|data: &[u8]| {
+    let data = match std::str::from_utf8(data) {
+        Ok(data) => data,
+        Err(_) => return,
+    };
+    let level_set = match LevelSet::parse(data) {
+        Ok(level_set) => level_set,
+        Err(_) => return,
+    };
+    let registry = Rc::new(Registry::default());
+    for level in level_set.levels.iter() {
+        let mut board = Board::from(level, 0, level_set.rules, &registry);
+        for _ in 0..TICKS {
+            board.tick();
+        }
+    }
+});
@@ -0,0 +1,75 @@
+//! Builds a random, well formed level from the known symbols and plays it with random input
+#![no_main]
+use libfuzzer_sys::arbitrary::{Result, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use rust_robbo::engine::{Board, LevelSet, Registry};
+use std::rc::Rc;
+
+/// Registry symbols plus one unknown symbol
+const SYMBOLS: &[u8] = b".OoHT'%RD#&!~b?V@*^}LlM=-QqpPsSX";
+const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+const MAX_SIZE: u8 = 24;
+const MAX_ADDITIONAL: u8 = 12;
+const MAX_TICKS: u16 = 300;
+
+fn symbol(u: &mut Unstructured) -> Result<char> {
+    Ok(*u.choose(SYMBOLS)? as char)
+}
+
+/// `[additional]` line, the position may lie just outside of the level
+fn additional(u: &mut Unstructured, width: u8, height: u8) -> Result<String> {
+    let mut parts = vec![
+        u.int_in_range(0..=width)?.to_string(),
+        u.int_in_range(0..=height)?.to_string(),
+        symbol(u)?.to_string(),
+    ];
+    for _ in 0..u.int_in_range(0..=8)? {
+        parts.push(u.arbitrary::<u16>()?.to_string());
+    }
+    Ok(parts.join("."))
+}
+
+fn pack(u: &mut Unstructured) -> Result<String> {
+    let width = u.int_in_range(1..=MAX_SIZE)?;
+    let height = u.int_in_range(1..=MAX_SIZE)?;
+    let mut rows = Vec::new();
+    for _ in 0..height {
+        let row = (0..width).map(|_| symbol(u)).collect::<Result<String>>()?;
+        rows.push(row);
+    }
+    let mut lines = Vec::new();
+    for _ in 0..u.int_in_range(0..=MAX_ADDITIONAL)? {
+        lines.push(additional(u, width, height)?);
+    }
+    Ok(format!(
+        "[name]\nFuzz\n[level]\n1\n[size]\n{}.{}\n[data]\n{}\n[additional]\n{}\n{}\n[end]\n",
+        width,
+        height,
+        rows.join("\n"),
+        lines.len(),
+        lines.join("\n")
+    ))
+}
+
+fn play(u: &mut Unstructured) -> Result<()> {
+    let data = pack(u)?;
+    let seed = u.arbitrary()?;
+    let ticks = u.int_in_range(0..=MAX_TICKS)?;
+    let level_set = match LevelSet::parse(&data) {
+        Ok(level_set) => level_set,
+        Err(_) => return Ok(()),
+    };
+    let registry = Rc::new(Registry::default());
+    let mut board = Board::from(&level_set.levels[0], seed, level_set.rules, &registry);
+    for _ in 0..ticks {
+        if u.ratio(1, 4)? {
+            board.robbo_move_or_shot(*u.choose(&DIRS)?, u.arbitrary()?);
+        }
+        board.tick();
+    }
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = play(&mut Unstructured::new(data));
+});
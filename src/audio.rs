@@ -0,0 +1,273 @@
+use events::GameEvent;
+use log::log;
+use sound::Sound;
+use std::collections::HashMap;
+use types::Position;
+use wasm_bindgen::prelude::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::rc::Rc;
+
+/// Maximum number of voices started in a single tick, across all sounds
+const MAX_VOICES_PER_TICK: usize = 8;
+
+#[derive(Clone, Debug, PartialEq)]
+struct SoundDef {
+    file: String,
+    volume: f32,
+    voices: usize,
+    length: usize,
+}
+
+impl SoundDef {
+    fn new(file: &str) -> SoundDef {
+        SoundDef {
+            file: String::from(file),
+            volume: 1.0,
+            voices: 2,
+            length: 4,
+        }
+    }
+}
+
+/// Sound set manifest: audio file, volume and concurrency limit of every engine sound.
+/// Sounds missing in the set are taken from the `fallback` set, see `with_fallback`
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoundSet {
+    name: String,
+    fallback: String,
+    sounds: HashMap<Sound, SoundDef>,
+}
+
+#[wasm_bindgen]
+impl SoundSet {
+    pub fn parse(data: &str) -> SoundSet {
+        let mut sound_set = SoundSet::new();
+        let mut section = "";
+        for line in data.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+            if line.starts_with('[') {
+                section = line;
+                continue;
+            }
+            match section {
+                "[name]" => sound_set.name = String::from(line),
+                "[fallback]" => sound_set.fallback = String::from(line),
+                "[sounds]" => sound_set.parse_sound(line),
+                _ => log!("sound set {}: unexpected line: {}", sound_set.name, line),
+            }
+        }
+        sound_set
+    }
+
+    /// Fills sounds missing in this set with the ones from `fallback`
+    pub fn with_fallback(mut self, fallback: &SoundSet) -> SoundSet {
+        for (sound, def) in fallback.sounds.iter() {
+            self.sounds.entry(*sound).or_insert_with(|| def.clone());
+        }
+        self
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn fallback(&self) -> String {
+        self.fallback.clone()
+    }
+
+    /// Audio file path relative to the sounds directory
+    pub fn get_file(&self, sound: i16) -> Option<String> {
+        Sound::by_id(sound)
+            .and_then(|sound| self.sounds.get(&sound))
+            .map(|def| def.file.clone())
+    }
+
+    /// Ids of all sounds having an audio file, for preloading
+    pub fn get_sounds(&self) -> Vec<i16> {
+        let mut sounds: Vec<i16> = self.sounds.keys().map(|sound| *sound as i16).collect();
+        sounds.sort();
+        sounds
+    }
+}
+
+impl SoundSet {
+    pub fn new() -> SoundSet {
+        SoundSet {
+            name: String::from("default"),
+            fallback: String::new(),
+            sounds: HashMap::new(),
+        }
+    }
+
+    /// `sound file [volume [voices [length]]]` - `voices` is the number of copies
+    /// allowed to play at once, each one occupying its slot for `length` ticks
+    fn parse_sound(&mut self, line: &str) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let sound = parts.first().and_then(|name| Sound::by_name(name));
+        match (sound, parts.get(1)) {
+            (Some(sound), Some(file)) => {
+                let mut def = SoundDef::new(&format!("{}/{}", self.name, file));
+                def.volume = parts.get(2).and_then(|v| v.parse().ok()).unwrap_or(def.volume);
+                def.voices = parts.get(3).and_then(|v| v.parse().ok()).unwrap_or(def.voices);
+                def.length = parts.get(4).and_then(|v| v.parse().ok()).unwrap_or(def.length);
+                self.sounds.insert(sound, def);
+            }
+            _ => log!("sound set {}: invalid sound: {}", self.name, line),
+        }
+    }
+}
+
+impl Default for SoundSet {
+    fn default() -> SoundSet {
+        SoundSet::new()
+    }
+}
+
+/// Sound to be started by the audio backend.
+/// `pan` goes from -1.0 (left) to 1.0 (right)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Voice {
+    pub sound: i16,
+    pub gain: f32,
+    pub pan: f32,
+}
+
+/// Backend independent part of the audio: picks voices to start from sound events,
+/// applying volumes, per-sound concurrency limits and panning
+pub struct AudioEngine {
+    sound_set: SoundSet,
+    master_volume: f32,
+    volumes: HashMap<Sound, f32>,
+    tick: usize,
+    playing: Vec<(Sound, usize)>,
+}
+
+impl AudioEngine {
+    pub fn new(sound_set: SoundSet) -> AudioEngine {
+        AudioEngine {
+            sound_set,
+            master_volume: 1.0,
+            volumes: HashMap::new(),
+            tick: 0,
+            playing: Vec::new(),
+        }
+    }
+
+    pub fn set_sound_set(&mut self, sound_set: SoundSet) {
+        self.sound_set = sound_set;
+        self.playing.clear();
+    }
+
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.max(0.0);
+    }
+
+    pub fn set_volume(&mut self, sound: Sound, volume: f32) {
+        self.volumes.insert(sound, volume.max(0.0));
+    }
+
+    /// Voices to start for the sound events of a single tick;
+    /// `pan` maps a board position to the stereo position
+    pub fn update(&mut self, events: &[GameEvent], pan: &dyn Fn(Position) -> f32) -> Vec<Voice> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.playing.retain(|(_, end)| *end > tick);
+
+        let mut voices = Vec::new();
+        for event in events {
+            let (sound, pos) = match event {
+                GameEvent::Sound(sound, pos) => (*sound, *pos),
+                _ => continue,
+            };
+            if voices.len() >= MAX_VOICES_PER_TICK {
+                break;
+            }
+            let default_def = SoundDef::new("");
+            let def = self.sound_set.sounds.get(&sound).unwrap_or(&default_def);
+            let playing = self.playing.iter().filter(|(s, _)| *s == sound).count();
+            if playing >= def.voices {
+                continue;
+            }
+            let volume = self.volumes.get(&sound).cloned().unwrap_or(1.0);
+            self.playing.push((sound, tick + def.length));
+            voices.push(Voice {
+                sound: sound as i16,
+                gain: def.volume * volume * self.master_volume,
+                pan: pos.map(pan).unwrap_or(0.0).clamp(-1.0, 1.0),
+            });
+        }
+        voices
+    }
+}
+
+/// Software mixer for native frontends. Clips are mono PCM samples
+/// at the output sample rate, output is interleaved stereo.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct Mixer {
+    clips: HashMap<Sound, Rc<Vec<f32>>>,
+    playing: Vec<Playing>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Playing {
+    clip: Rc<Vec<f32>>,
+    offset: usize,
+    left: f32,
+    right: f32,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Mixer {
+    pub fn new() -> Mixer {
+        Mixer {
+            clips: HashMap::new(),
+            playing: Vec::new(),
+        }
+    }
+
+    pub fn load(&mut self, sound: Sound, samples: Vec<f32>) {
+        self.clips.insert(sound, Rc::new(samples));
+    }
+
+    pub fn play(&mut self, voice: &Voice) {
+        let clip = Sound::by_id(voice.sound).and_then(|sound| self.clips.get(&sound));
+        if let Some(clip) = clip {
+            // equal power panning
+            let angle = (voice.pan + 1.0) * std::f32::consts::FRAC_PI_4;
+            self.playing.push(Playing {
+                clip: clip.clone(),
+                offset: 0,
+                left: angle.cos() * voice.gain,
+                right: angle.sin() * voice.gain,
+            });
+        }
+    }
+
+    /// Mixes playing voices into `out`, overwriting its content
+    pub fn mix(&mut self, out: &mut [f32]) {
+        for sample in out.iter_mut() {
+            *sample = 0.0;
+        }
+        for voice in self.playing.iter_mut() {
+            for frame in out.chunks_mut(2) {
+                let value = match voice.clip.get(voice.offset) {
+                    Some(value) => *value,
+                    None => break,
+                };
+                voice.offset += 1;
+                frame[0] += value * voice.left;
+                if let Some(right) = frame.get_mut(1) {
+                    *right += value * voice.right;
+                }
+            }
+        }
+        self.playing.retain(|voice| voice.offset < voice.clip.len());
+        for sample in out.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+}
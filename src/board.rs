@@ -1,159 +1,263 @@
-use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
 
-use rand::SeedableRng;
+use consts;
 
 use crate::items::{
-    Animation, Bear, Bird, BlastHead, Bomb, Bullet, Butterfly, Capsule, Door, ForceField, Gun,
-    GunType, Item, LaserHead, Magnet, PushBox, Robbo, SimpleItem, Teleport,
+    Animation, BlastHead, Bullet, ForceField, GunType, Item, ItemKind, LaserHead, Robbo,
+    SimpleItem, Teleport,
 };
+use leaderboard::RunRecord;
 use levels::Level;
+use log::log;
+use moves::{Move, Moves};
 use rand::Rng;
-use sound::{Sound, Sounds};
+use registry::{ObjectDef, Registry};
+use rng::GameRng;
+use rules::RuleSet;
+use events::{Events, GameEvent};
+use sound::Sound;
+use stats::Stats;
 use tiles::{Tile, Tiles};
+use death::{Death, DeathCause};
 use types::{Action, Actions, Direction, Kind, Position};
 use utils::{dest_coords, direction_by_index};
+use wasm_bindgen::prelude::*;
 
+/// Number of `Kind` variants, size of the per-kind index
+const KINDS: usize = Kind::Explosion as usize + 1;
+
+/// Items stored in a dense grid of slots, plus an index of occupied slots per kind.
+/// Slots are laid out so that iterating them visits positions in `(x, y)` order.
 pub struct Items {
-    items: HashMap<Position, Box<dyn Item>>,
-    processed: HashSet<Position>,
+    height: i32,
+    width: i32,
+    slots: Vec<Option<ItemKind>>,
+    processed: Vec<u32>,
+    generation: u32,
+    by_kind: Vec<Vec<usize>>,
+    // position of every occupied slot within its `by_kind` list
+    kind_index: Vec<usize>,
 }
 
 impl Items {
-    pub fn new(items: Vec<Box<dyn Item>>) -> Items {
-        let mut hashmap = HashMap::new();
+    pub fn new(width: i32, height: i32, items: Vec<ItemKind>) -> Items {
+        let size = (width.max(0) * height.max(0)) as usize;
+        let mut slots = Vec::with_capacity(size);
+        slots.resize_with(size, || None);
+        let mut result = Items {
+            height,
+            width,
+            slots,
+            processed: vec![0; size],
+            generation: 0,
+            by_kind: vec![Vec::new(); KINDS],
+            kind_index: vec![0; size],
+        };
         for item in items {
-            hashmap.insert(item.get_position(), item);
+            let pos = item.get_position();
+            result.remove(pos);
+            result.insert(item);
         }
-        Items {
-            items: hashmap,
-            processed: HashSet::new(),
+        result
+    }
+    fn index(&self, (x, y): Position) -> Option<usize> {
+        if x >= 0 && y >= 0 && x < self.width && y < self.height {
+            Some((x * self.height + y) as usize)
+        } else {
+            None
         }
     }
     fn init(&mut self) {
-        self.processed = HashSet::new();
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            self.processed.iter_mut().for_each(|v| *v = 0);
+            self.generation = 1;
+        }
     }
-    pub fn get_items(&self, kind: Kind) -> Vec<&Box<dyn Item>> {
-        self.items
-            .values()
-            .filter(|v| v.get_kind() == kind)
+    pub fn get_items(&self, kind: Kind) -> Vec<&ItemKind> {
+        self.by_kind[kind as usize]
+            .iter()
+            .filter_map(|index| self.slots[*index].as_ref())
             .collect()
     }
-    fn mut_item_at(&mut self, pos: Position) -> Option<&mut Box<dyn Item>> {
-        self.items.get_mut(&pos)
+    fn mut_item_at(&mut self, pos: Position) -> Option<&mut ItemKind> {
+        self.get_mut(pos)
     }
-    pub fn item_at(&self, pos: Position) -> Option<&Box<dyn Item>> {
-        self.items.get(&pos)
+    pub fn item_at(&self, pos: Position) -> Option<&ItemKind> {
+        self.index(pos).and_then(|index| self.slots[index].as_ref())
     }
-    fn iter_mut(
-        &mut self,
-    ) -> std::collections::hash_map::ValuesMut<'_, (i32, i32), Box<(dyn Item)>> {
-        self.items.values_mut()
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut ItemKind> {
+        self.slots.iter_mut().flatten()
     }
-    fn item_positions_to_process(&self) -> Vec<Position> {
-        let mut keys: Vec<Position> = self.items.keys().cloned().collect();
-        keys.sort();
-        keys
+    /// Positions of all items, in processing order
+    fn positions(&self) -> Vec<Position> {
+        (0..self.slot_count())
+            .map(|index| self.slot_position(index))
+            .filter(|pos| self.item_at(*pos).is_some())
+            .collect()
+    }
+    fn slot_count(&self) -> usize {
+        self.slots.len()
+    }
+    fn slot_position(&self, index: usize) -> Position {
+        let index = index as i32;
+        (index / self.height, index % self.height)
     }
     fn is_processed(&self, pos: Position) -> bool {
-        self.processed.contains(&pos)
+        self.index(pos)
+            .map(|index| self.processed[index] == self.generation)
+            .unwrap_or(false)
     }
-    pub fn push(&mut self, item: Box<dyn Item>) {
+    fn insert(&mut self, item: ItemKind) -> usize {
+        let pos = item.get_position();
+        let index = self
+            .index(pos)
+            .unwrap_or_else(|| panic!("item: {:?} outside of the board", item));
         assert!(
-            !self.items.contains_key(&item.get_position()),
+            self.slots[index].is_none(),
             "item: {:?} already contains: {:?}",
             item,
-            self.items.get(&item.get_position())
+            self.slots[index]
         );
-        let pos = item.get_position();
-        self.items.insert(pos, item);
-        self.processed.insert(pos);
+        let kind_list = &mut self.by_kind[item.get_kind() as usize];
+        self.kind_index[index] = kind_list.len();
+        kind_list.push(index);
+        self.slots[index] = Some(item);
+        index
     }
-    fn get_mut(&mut self, pos: Position) -> Option<&mut Box<dyn Item>> {
-        self.items.get_mut(&pos)
+    pub fn push(&mut self, item: ItemKind) {
+        let index = self.insert(item);
+        self.processed[index] = self.generation;
     }
-    pub fn remove(&mut self, pos: Position) -> Option<Box<dyn Item>> {
-        self.items.remove(&pos)
+    fn get_mut(&mut self, pos: Position) -> Option<&mut ItemKind> {
+        match self.index(pos) {
+            Some(index) => self.slots[index].as_mut(),
+            None => None,
+        }
+    }
+    pub fn remove(&mut self, pos: Position) -> Option<ItemKind> {
+        let index = self.index(pos)?;
+        let item = self.slots[index].take()?;
+        let kind_list = &mut self.by_kind[item.get_kind() as usize];
+        let kind_index = self.kind_index[index];
+        kind_list.swap_remove(kind_index);
+        if let Some(moved) = kind_list.get(kind_index) {
+            self.kind_index[*moved] = kind_index;
+        }
+        Some(item)
+    }
+    /// Disagreements between the slots, item positions and the per-kind index
+    #[cfg(debug_assertions)]
+    fn check(&self) -> Vec<String> {
+        let mut violations = Vec::new();
+        for (index, slot) in self.slots.iter().enumerate() {
+            let item = match slot {
+                Some(item) => item,
+                None => continue,
+            };
+            let pos = self.slot_position(index);
+            if item.get_position() != pos {
+                violations.push(format!(
+                    "{:?}: item {:?} claims position {:?}",
+                    pos,
+                    item.get_kind(),
+                    item.get_position()
+                ));
+            }
+            let kind_list = &self.by_kind[item.get_kind() as usize];
+            if kind_list.get(self.kind_index[index]) != Some(&index) {
+                violations.push(format!("{:?}: item {:?} missing in the kind index", pos, item.get_kind()));
+            }
+        }
+        for (kind, kind_list) in self.by_kind.iter().enumerate() {
+            for index in kind_list {
+                let item_kind = self.slots[*index].as_ref().map(|item| item.get_kind() as usize);
+                if item_kind != Some(kind) {
+                    violations.push(format!(
+                        "{:?}: kind index entry without a matching item",
+                        self.slot_position(*index)
+                    ));
+                }
+            }
+        }
+        violations
     }
 }
 
+/// Progress of the level towards its completion
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LevelState {
+    Playing,
+    /// Robbo entered the capsule, which is taking off
+    TakingOff,
+    /// the capsule is gone, the next level may be loaded
+    LevelComplete,
+}
+
+/// Restart of the level requested by the player (Esc)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Restart {
+    Idle,
+    /// waiting for Robbo to appear, while spawning or teleporting
+    Requested,
+    /// Robbo exploded, the level restarts like after any other death
+    Exploding,
+}
+
 pub struct Board {
-    pub rng: Box<dyn rand::RngCore>,
+    pub rng: GameRng,
+    pub seed: u64,
+    registry: Rc<Registry>,
     pub width: i32,
     pub height: i32,
     pub items: Items,
     pub tiles: Tiles,
     pub robbo: Robbo,
     pub missing_screws: usize,
+    // screws put on the board, by the level and by question marks
+    placed_screws: usize,
     pub missing_robbo_ticks: usize,
-    pub robbo_moving_dir: Option<Direction>,
-    pub robbo_shooting_dir: Option<Direction>,
-    pub finished: bool,
-    sounds: Sounds,
+    pub stats: Stats,
+    // Robbo has been shown once, the level timer runs from then on
+    spawned: bool,
+    level_state: LevelState,
+    // ticks since Robbo entered the capsule
+    take_off_ticks: usize,
+    death: Option<Death>,
+    restart: Restart,
+    events: Events,
+    // ambient sounds played during the current tick
+    ambient_sounds: Vec<Sound>,
+    moves: Moves,
 }
 
 impl Board {
-    pub fn from(level: &Level) -> Board {
+    pub fn from(level: &Level, seed: u64, rules: RuleSet, registry: &Rc<Registry>) -> Board {
         let mut items = Vec::new();
-        let mut tiles: Tiles = Tiles::new(level.width, level.height);
+        let mut tiles: Tiles = Tiles::new(level.width, level.height, rules);
         let mut missing_screws = 0;
         let mut robbo = Robbo::new();
         for (y, row) in level.tiles.iter().enumerate() {
             for (x, c) in row.chars().enumerate() {
                 let pos: Position = (x as i32, y as i32);
                 let additional = level.additional.get(&(x, y)).map(|v| &v[..]);
-
-                let tile_map: HashMap<char, Tile> = [
-                    ('O', Tile::wall(2)),
-                    ('o', Tile::wall(29)),
-                    ('-', Tile::wall(19)),
-                    ('Q', Tile::wall(3)),
-                    ('q', Tile::wall(21)),
-                    ('p', Tile::wall(68)),
-                    ('P', Tile::wall(69)),
-                    ('s', Tile::wall(10)),
-                    ('S', Tile::wall(22)),
-                    ('H', Tile::ground()),
-                    ('T', Tile::screw()),
-                    ('\'', Tile::ammo()),
-                    ('%', Tile::key()),
-                ]
-                .iter()
-                .cloned()
-                .collect();
-                let tile = tile_map.get(&c);
-                if let Some(tile) = tile {
-                    if tile.get_kind() == Kind::Screw {
-                        missing_screws += 1
+                let mut item = match registry.get(c) {
+                    Some(ObjectDef::Tile(tile)) => {
+                        if tile.get_kind() == Kind::Screw {
+                            missing_screws += 1
+                        }
+                        tiles.put(pos, *tile);
+                        continue;
                     }
-                    tiles.put(pos, tile.clone());
-                    continue;
-                }
-                let mut item: Box<dyn Item> = match c {
-                    'D' => Box::new(Door::new()),
-                    '#' => Box::new(SimpleItem::abox()),
-                    '&' => Box::new(Teleport::new(additional.unwrap_or(&[0, 0]))),
-                    'R' => {
-                        robbo.set_position(pos);
-                        Box::new(Animation::spawn_robbo())
+                    Some(ObjectDef::Item(def)) => {
+                        if def.kind == Kind::Robbo {
+                            robbo.set_position(pos);
+                        }
+                        def.create(additional)
                     }
-                    '!' => Box::new(Capsule::new()),
-                    '~' => Box::new(PushBox::new()),
-                    'b' => Box::new(Bomb::new()),
-                    '?' => Box::new(SimpleItem::questionmark()),
-                    'V' => Box::new(Butterfly::new()),
-                    '@' => Box::new(Bear::new(Kind::Bear, additional.unwrap_or(&[0]), &[13, 14])),
-                    '*' => Box::new(Bear::new(
-                        Kind::BlackBear,
-                        additional.unwrap_or(&[0]),
-                        &[30, 31],
-                    )),
-                    '^' => Box::new(Bird::new(additional.unwrap_or(&[0, 0, 0]))),
-                    '}' => Box::new(Gun::new(additional.unwrap_or(&[0, 0, 0, 0, 0, 0]))),
-                    'L' => Box::new(SimpleItem::horizontal_laser()),
-                    'l' => Box::new(SimpleItem::vertical_laser()),
-                    'M' => Box::new(Magnet::new(additional.unwrap_or(&[0]))),
-                    '=' => Box::new(ForceField::new(additional.unwrap_or(&[0]))),
-                    _ => continue,
+                    None => continue,
                 };
                 item.set_position(pos);
                 item.put_tile(&mut tiles);
@@ -161,18 +265,26 @@ impl Board {
             }
         }
         let board = Board {
-            rng: Box::new(rand::rngs::SmallRng::seed_from_u64(0)),
+            rng: GameRng::new(seed),
+            seed,
+            registry: registry.clone(),
             width: level.width,
             height: level.height,
-            items: Items::new(items),
-            robbo: robbo,
-            tiles: tiles,
+            items: Items::new(level.width, level.height, items),
+            robbo,
+            tiles,
             missing_screws,
-            robbo_moving_dir: None,
-            robbo_shooting_dir: None,
-            finished: false,
-            sounds: Sounds::new(),
+            placed_screws: missing_screws,
+            level_state: LevelState::Playing,
+            take_off_ticks: 0,
+            death: None,
+            restart: Restart::Idle,
+            events: Events::new(),
+            ambient_sounds: Vec::new(),
+            moves: Moves::new(),
             missing_robbo_ticks: 0,
+            stats: Stats::new(),
+            spawned: false,
         };
         board.play_sound(Sound::Spawn);
         board
@@ -185,7 +297,10 @@ impl Board {
         }
         self.items
             .item_at(pos)
-            .and_then(|item| item.as_magnet())
+            .and_then(|item| match item {
+                ItemKind::Magnet(magnet) => Some(magnet),
+                _ => None,
+            })
             .filter(|magnet| magnet.get_magnetic_force_dir() == dir)
             .map(|_magnet| dir)
     }
@@ -194,6 +309,20 @@ impl Board {
         self.tiles.get(pos).map(|x| x.get_tile()).unwrap_or(0)
     }
 
+    /// Tile at `pos` sampled `alpha` (0..1) of the way towards the next tick.
+    /// With `alpha == 0.0` this is the same as `get_tile`.
+    pub fn get_tile_at(&self, pos: Position, alpha: f64) -> usize {
+        // tiles were put during the last tick, before frame_cnt was advanced
+        let time = self.tiles.frame_cnt.saturating_sub(1) as f64 + alpha.clamp(0.0, 1.0);
+        if !self.robbo.is_hidden && pos == self.robbo.get_position() {
+            return self.robbo.get_tile_at(time);
+        }
+        match self.items.item_at(pos) {
+            Some(item) => item.get_tile_at(time),
+            None => self.get_tile(pos),
+        }
+    }
+
     pub fn god_mode2(&mut self) {
         self.robbo.inventory.bullets = 99999;
         self.play_sound(Sound::Bomb)
@@ -204,25 +333,28 @@ impl Board {
             .items
             .get_items(Kind::Gun)
             .iter()
-            .map(|i| i.as_gun())
-            .flatten()
+            .filter_map(|item| match item {
+                ItemKind::Gun(gun) => Some(gun),
+                _ => None,
+            })
             .all(|x| x.disabled);
 
-        for pos in self.items.item_positions_to_process() {
+        for pos in self.items.positions() {
             let kind = self.tiles.get_kind(pos);
             match kind {
                 Kind::Butterfly | Kind::Bear | Kind::BlackBear | Kind::Bird => {
-                    self.destroy(pos, true)
+                    self.destroy(pos, true, DeathCause::Impact(Kind::Explosion, pos))
                 }
                 Kind::Gun => {
-                    if let Some(item) = self.items.mut_item_at(pos) {
-                        item.as_mut_gun()
-                            .map(|item| item.disabled = !all_guns_disabled);
+                    if let Some(ItemKind::Gun(gun)) = self.items.mut_item_at(pos) {
+                        gun.disabled = !all_guns_disabled;
                     }
                 }
                 Kind::Capsule => {
-                    if let Some(item) = self.items.mut_item_at(pos) {
-                        item.as_mut_capsule().unwrap().repair();
+                    if let Some(ItemKind::Capsule(capsule)) = self.items.mut_item_at(pos) {
+                        if capsule.repair() {
+                            self.emit(GameEvent::CapsuleRepaired);
+                        }
                     }
                 }
                 _ => (),
@@ -231,8 +363,18 @@ impl Board {
         self.play_sound(Sound::Bomb)
     }
 
-    pub fn remove_at(&mut self, pos: Position) -> Option<Box<dyn Item>> {
-        if pos == self.robbo.get_position() {
+    /// Skips the spawn animation, Robbo appears at once
+    pub fn spawn_robbo(&mut self) {
+        let pos = self.robbo.get_position();
+        if self.robbo.is_hidden && self.tiles.get_kind(pos) == Kind::Explosion {
+            self.items.remove(pos);
+            self.robbo.show(&mut self.tiles);
+            self.spawned = true;
+        }
+    }
+
+    pub fn remove_at(&mut self, pos: Position) -> Option<ItemKind> {
+        if !self.robbo.is_hidden && pos == self.robbo.get_position() {
             self.robbo.hide(&mut self.tiles);
             // return None;
         }
@@ -241,63 +383,107 @@ impl Board {
         item
     }
 
+    /// Removes whatever is at `pos` without an explosion, Robbo dies of `cause`
+    pub fn consume(&mut self, pos: Position, cause: DeathCause) {
+        if pos == self.robbo.get_position() && !self.robbo.is_hidden {
+            self.report_death(cause);
+        }
+        self.remove_at(pos);
+    }
+
     pub fn mv(&mut self, pos: Position, dir: Direction) -> Option<Position> {
-        let x = self.items.remove(pos);
-        if x.is_some() {
-            let mut item = x.unwrap();
+        if let Some(mut item) = self.items.remove(pos) {
             item._mv(dir, &mut self.tiles);
-            let pos = item.get_position();
+            let dest = item.get_position();
+            self.record_move(item.get_kind(), pos, dest, true);
             self.items.push(item);
-            Some(pos)
+            Some(dest)
         } else {
             None
         }
     }
 
-    pub fn add_item(&mut self, pos: Position, mut item: Box<dyn Item>) {
+    /// Places object registered for the level symbol, like `Board::from` does
+    pub fn create_object(&mut self, pos: Position, symbol: char, params: Option<&[u16]>) {
+        match self.registry.get(symbol) {
+            Some(ObjectDef::Tile(tile)) => {
+                if tile.get_kind() == Kind::Screw {
+                    self.placed_screws += 1;
+                }
+                self.tiles.put(pos, *tile)
+            }
+            Some(ObjectDef::Item(def)) => {
+                let item = def.create(params);
+                self.add_item(pos, item)
+            }
+            None => log!("unknown symbol: {}", symbol),
+        }
+    }
+
+    pub fn add_item(&mut self, pos: Position, mut item: ItemKind) {
+        if self.tiles.get(pos).is_none() {
+            log!("item outside of the board: {:?}", item);
+            return;
+        }
         self.remove_at(pos);
         item.set_position(pos);
         item.put_tile(&mut self.tiles);
         self.items.push(item);
     }
 
-    pub fn destroy(&mut self, pos: Position, force: bool) {
+    pub fn destroy(&mut self, pos: Position, force: bool, cause: DeathCause) {
         let tile = self.tiles.get_or_wall(pos);
         if tile.get_kind() == Kind::Bomb {
             if let Some(item) = self.items.mut_item_at(pos) {
-                item.destroy();
+                if item.destroy() {
+                    self.stats.bombs += 1;
+                    self.emit(GameEvent::ItemDestroyed(Kind::Bomb, pos));
+                }
                 return;
             }
         }
         if tile.is_destroyable() {
-            self.play_sound(Sound::Burn)
+            self.play_sound_at(Sound::Burn, pos)
         }
         if tile.get_kind() == Kind::Robbo {
+            self.play_sound_at(Sound::Kill, pos);
+            self.report_death(cause);
             self.robbo.hide(&mut self.tiles);
-            self.add_item(pos, Box::new(Animation::kill_robbo()));
+            self.add_item(pos, Animation::kill_robbo().into());
             return;
         }
         let is_bomb_destroyable = !tile.is_undestroyable();
         if tile.is_destroyable() || force && is_bomb_destroyable {
+            if tile.is_enemy() {
+                self.stats.kills += 1;
+                self.robbo.inventory.score += consts::ENEMY_SCORE;
+            }
+            self.emit(GameEvent::ItemDestroyed(tile.get_kind(), pos));
             let animation = if tile.get_kind() == Kind::Questionmark {
                 Animation::question_mark_explosion()
             } else {
                 Animation::small_explosion()
             };
-            self.add_item(pos, Box::new(animation));
+            self.add_item(pos, animation.into());
         }
     }
 
     pub fn dispatch_actions(&mut self, mut actions: Actions, pos: Position) {
+        let source_cause = self
+            .items
+            .item_at(pos)
+            .map(|item| item.get_death_cause())
+            .unwrap_or_else(|| DeathCause::Impact(self.tiles.get_kind(pos), pos));
         while let Some(action) = actions.pop_first() {
             match action {
-                Action::PlaySound(sound) => self.play_sound(sound),
+                Action::PlaySound(sound) => self.play_sound_at(sound, pos),
+                Action::PlayAmbientSound(sound) => self.play_ambient_sound_at(sound, pos),
+                Action::Emit(event) => self.emit(event),
                 Action::RelMove(direction) => {
                     self.mv(pos, direction);
                 }
                 Action::ForceRelMove(dir) => {
-                    let dest_pos = dest_coords(pos, dir);
-                    self.remove_at(dest_pos);
+                    self.consume(dest_coords(pos, dir), source_cause);
                     self.mv(pos, dir);
                 }
                 Action::RobboMove(dir) => {
@@ -305,19 +491,26 @@ impl Board {
                     let dst_tile = self.tiles.get_or_wall(dst);
                     if dst_tile.is_collectable() {
                         actions.extend(&self.robbo.inventory.collect(dst_tile.get_kind()));
+                        self.emit(GameEvent::ItemCollected(dst_tile.get_kind(), dst));
                         self.remove_at(dst);
                     }
                     let dst_tile = self.tiles.get_or_wall(dst);
                     if dst_tile.is_empty() {
                         if self.robbo._mv(dir, &mut self.tiles) {
-                            self.play_sound(Sound::Walk);
+                            self.record_move(Kind::Robbo, pos, dst, true);
+                            self.count_step();
+                            self.play_sound_at(Sound::Walk, dst);
                         };
                     } else if dst_tile.is_moveable() {
                         if let Some(dest_pos) = self.mv(dst, dir) {
                             let item = self.items.get_mut(dest_pos).unwrap();
                             item.pushed(dir);
+                            self.stats.pushes += 1;
+                            self.play_sound_at(Sound::Push, dest_pos);
                             if self.robbo._mv(dir, &mut self.tiles) {
-                                self.play_sound(Sound::Walk);
+                                self.record_move(Kind::Robbo, pos, dst, true);
+                                self.count_step();
+                                self.play_sound_at(Sound::Walk, dst);
                             }
                         };
                     } else {
@@ -329,50 +522,57 @@ impl Board {
                 Action::AutoRemove => {
                     self.remove_at(pos);
                 }
-                Action::NextLevel => {
-                    self.finished = true;
-                    self.play_sound(Sound::Capsule);
-                }
+                Action::EnterCapsule(capsule_pos) => self.enter_capsule(capsule_pos),
                 Action::RelImpact(direction, force) => {
                     let dest = dest_coords(pos, direction);
-                    self.destroy(dest, force);
+                    self.destroy(dest, force, source_cause);
+                }
+                Action::CreateBullet(direction) => {
+                    if !self.robbo.is_hidden && pos == self.robbo.get_position() {
+                        self.stats.shots += 1;
+                    }
+                    self._shot(pos, direction, GunType::Burst)
                 }
-                Action::CreateBullet(direction) => self._shot(pos, direction, GunType::Burst),
                 Action::CreateLaser(direction) => self._shot(pos, direction, GunType::Solid),
                 Action::CreateBlast(direction) => self._shot(pos, direction, GunType::Blaster),
                 Action::CreateLaserTail(pos, dir) => {
-                    self.add_item(pos, Box::new(SimpleItem::laser_tail(dir)))
+                    self.add_item(pos, SimpleItem::laser_tail(dir).into())
                 }
                 Action::CreateBlastTail(pos, _dir) => {
-                    self.add_item(pos, Box::new(Animation::blast_tail()))
+                    self.add_item(pos, Animation::blast_tail().into())
                 }
                 Action::SmallExplosion => {
-                    self.add_item(pos, Box::new(Animation::small_explosion()));
-                    self.play_sound(Sound::GunShot);
+                    self.add_item(pos, Animation::small_explosion().into());
+                    self.play_sound_at(Sound::GunShot, pos);
                 }
-                Action::SpawnRobbo => {
+                // spawns of extra `R` symbols just vanish
+                Action::SpawnRobbo if pos == self.robbo.get_position() => {
                     self.robbo.show(&mut self.tiles);
+                    self.spawned = true;
                 }
-                Action::KillRobbo => self.kill_robbo(),
+                Action::SpawnRobbo => (),
+                Action::KillRobbo(cause) => self.kill_robbo(cause),
                 Action::ExplodeAll => self.robbo.kill(),
                 Action::SpawnRandomItem => {
                     // empty field, push box, screw, bullet, key, bomb, ground, butterfly, gun or another questionmark
                     match self.rng.gen::<u32>() % 10 {
-                        1 => self.tiles.put(pos, Tile::screw()),
-                        2 => self.tiles.put(pos, Tile::ammo()),
-                        3 => self.tiles.put(pos, Tile::key()),
-                        4 => self.tiles.put(pos, Tile::ground()),
-                        5 => self.add_item(pos, Box::new(PushBox::new())),
-                        6 => self.add_item(pos, Box::new(Bomb::new())),
-                        7 => self.add_item(pos, Box::new(Butterfly::new())),
-                        8 => self.add_item(pos, Box::new(Gun::new(&[0, 0, 0, 0, 0, 1]))),
-                        9 => self.add_item(pos, Box::new(SimpleItem::questionmark())),
-                        _ => self.add_item(pos, Box::new(Animation::small_explosion())),
+                        1 => self.create_object(pos, 'T', None),
+                        2 => self.create_object(pos, '\'', None),
+                        3 => self.create_object(pos, '%', None),
+                        4 => self.create_object(pos, 'H', None),
+                        5 => self.create_object(pos, '~', None),
+                        6 => self.create_object(pos, 'b', None),
+                        7 => self.create_object(pos, 'V', None),
+                        8 => self.create_object(pos, '}', Some(&[0, 0, 0, 0, 0, 1])),
+                        9 => self.create_object(pos, '?', None),
+                        _ => self.add_item(pos, Animation::small_explosion().into()),
                     };
                 }
                 Action::TeleportRobbo(group, position_in_group, direction) => {
-                    Teleport::teleport_robbo(self, group, position_in_group, direction);
-                    self.play_sound(Sound::Teleport);
+                    if Teleport::teleport_robbo(self, group, position_in_group, direction) {
+                        self.stats.teleports += 1;
+                    }
+                    self.play_sound_at(Sound::Teleport, pos);
                 }
                 Action::ForceField => {
                     ForceField::process_force_field(self, pos);
@@ -382,31 +582,53 @@ impl Board {
     }
     pub fn tick(&mut self) {
         self.items.init();
-        self.tiles.magnetic_force_dir = (0..4)
+        self.moves.clear();
+        self.ambient_sounds.clear();
+        self.process_restart();
+        match self.level_state {
+            LevelState::Playing if self.spawned => self.stats.ticks += 1,
+            LevelState::Playing => (),
+            _ => self.take_off(),
+        }
+        let magnetic_force_dir = (0..4)
             .map(direction_by_index)
             .map(|dir| self.get_magnetic_force_dir(self.robbo.get_position(), dir))
             .find(|dir| dir.is_some())
             .unwrap_or(None);
+        // the pull is heard once, when it starts
+        if magnetic_force_dir.is_some()
+            && self.tiles.magnetic_force_dir.is_none()
+            && !self.robbo.is_hidden
+        {
+            self.play_sound_at(Sound::Magnet, self.robbo.get_position());
+        }
+        self.tiles.magnetic_force_dir = magnetic_force_dir;
 
-        let actions = self.robbo.tick(&mut self.tiles, self.rng.as_mut());
+        let actions = self.robbo.tick(&self.tiles, &mut self.rng);
         self.dispatch_actions(actions, self.robbo.get_position());
 
         self.tiles.robbo_pos = Some(self.robbo.get_position());
 
-        for pos in self.items.item_positions_to_process() {
+        // items arriving at a slot during the tick are marked as processed,
+        // so walking the slots visits the same items as a sorted snapshot would
+        for index in 0..self.items.slot_count() {
+            let pos = self.items.slot_position(index);
             if self.items.is_processed(pos) {
                 continue;
             }
             if let Some(item) = self.items.get_mut(pos) {
-                let actions = item.tick(&mut self.tiles, self.rng.as_mut());
+                let actions = item.tick(&self.tiles, &mut self.rng);
                 item.put_tile(&mut self.tiles);
                 self.dispatch_actions(actions, pos);
             }
         }
 
-        if self.robbo.is_hidden {
+        if self.robbo.is_hidden && self.level_state == LevelState::Playing {
             self.missing_robbo_ticks += 1;
-            if self.missing_robbo_ticks == 8 {
+            if self.missing_robbo_ticks == self.tiles.rules.hidden_robbo_explode_ticks {
+                if self.death.is_none() {
+                    self.report_death(DeathCause::Vanished);
+                }
                 self.explode_all();
             }
         } else {
@@ -414,11 +636,10 @@ impl Board {
         }
 
         let robbo_neighbours = self.tiles.get_neighbours(self.robbo.get_position());
-        if !self.robbo.is_hidden && robbo_neighbours.is_deadly() {
-            self.dispatch_actions(
-                Actions::single(Action::KillRobbo),
-                self.robbo.get_position(),
-            );
+        if let Some((kind, dir)) = robbo_neighbours.get_deadly().filter(|_| !self.robbo.is_hidden) {
+            let robbo_pos = self.robbo.get_position();
+            let cause = DeathCause::Deadly(kind, dest_coords(robbo_pos, dir));
+            self.dispatch_actions(Actions::single(Action::KillRobbo(cause)), robbo_pos);
         }
 
         if !self.robbo.is_hidden && self.robbo.inventory.screws >= self.missing_screws {
@@ -426,45 +647,248 @@ impl Board {
         }
 
         self.tiles.frame_cnt += 1;
+
+        #[cfg(debug_assertions)]
+        self.check_invariants();
+    }
+
+    /// Panics when items, tiles, Robbo and the screw counters disagree,
+    /// listing every disagreement followed by the board; logs it on wasm32 instead
+    #[cfg(debug_assertions)]
+    pub fn check_invariants(&self) {
+        let mut violations = self.items.check();
+        let robbo_pos = self.robbo.get_position();
+        let mut screws = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pos = (x, y);
+                let kind = self.tiles.get_kind(pos);
+                let item_kind = self.items.item_at(pos).map(|item| item.get_kind());
+                let is_robbo = !self.robbo.is_hidden && pos == robbo_pos;
+                let expected = match kind {
+                    Kind::Empty | Kind::Wall | Kind::Ground | Kind::Ammo | Kind::Key => None,
+                    Kind::Screw => {
+                        screws += 1;
+                        None
+                    }
+                    Kind::Robbo if is_robbo => None,
+                    Kind::Robbo => {
+                        violations.push(format!("{:?}: Robbo tile, Robbo is at {:?}", pos, robbo_pos));
+                        None
+                    }
+                    kind => Some(kind),
+                };
+                if item_kind != expected {
+                    violations.push(format!("{:?}: tile {:?}, item {:?}", pos, kind, item_kind));
+                }
+                if is_robbo && kind != Kind::Robbo {
+                    violations.push(format!("{:?}: Robbo is shown on a {:?} tile", pos, kind));
+                }
+            }
+        }
+        if screws + self.robbo.inventory.screws > self.placed_screws {
+            violations.push(format!(
+                "{} screws on the board and {} collected, only {} placed",
+                screws, self.robbo.inventory.screws, self.placed_screws
+            ));
+        }
+        if self.missing_screws > self.placed_screws {
+            violations.push(format!(
+                "capsule needs {} screws, only {} placed",
+                self.missing_screws, self.placed_screws
+            ));
+        }
+        if violations.is_empty() {
+            return;
+        }
+        let report = format!(
+            "board invariants violated at tick {}:\n{}\ntiles | items\n{}",
+            self.tiles.frame_cnt,
+            violations.join("\n"),
+            self.render_items()
+        );
+        // a panic would stop the game in a browser dev build
+        #[cfg(target_arch = "wasm32")]
+        log!("{}", report);
+        #[cfg(not(target_arch = "wasm32"))]
+        panic!("{}", report);
+    }
+
+    /// Tiles and items (with Robbo) side by side, in the symbols of `Display`
+    #[cfg(debug_assertions)]
+    fn render_items(&self) -> String {
+        let robbo_pos = Some(self.robbo.get_position()).filter(|_| !self.robbo.is_hidden);
+        let mut out = String::new();
+        for y in 0..self.height {
+            let tiles: String = (0..self.width)
+                .map(|x| self.symbol(self.tiles.get_or_wall((x, y))))
+                .collect();
+            let items: String = (0..self.width)
+                .map(|x| match self.items.item_at((x, y)) {
+                    _ if robbo_pos == Some((x, y)) => 'R',
+                    Some(item) => self.symbol(Tile::new(
+                        item.get_kind(),
+                        item.get_flags(),
+                        item.get_tile(self.tiles.frame_cnt),
+                    )),
+                    None => ' ',
+                })
+                .collect();
+            out.push_str(&format!("{} | {}\n", tiles, items));
+        }
+        out
+    }
+
+    /// Robbo disappears into the repaired capsule, which takes off
+    fn enter_capsule(&mut self, capsule_pos: Position) {
+        if self.level_state != LevelState::Playing {
+            return;
+        }
+        let robbo_pos = self.robbo.get_position();
+        self.level_state = LevelState::TakingOff;
+        self.robbo.hide(&mut self.tiles);
+        self.record_move(Kind::Robbo, robbo_pos, capsule_pos, true);
+        self.add_item(capsule_pos, Animation::capsule_take_off().into());
+        self.play_sound_at(Sound::LevelEnd, capsule_pos);
+        self.emit(GameEvent::LevelFinished);
+    }
+
+    /// The level is complete `level_complete_ticks` after Robbo entered the capsule
+    fn take_off(&mut self) {
+        if self.level_state != LevelState::TakingOff {
+            return;
+        }
+        self.take_off_ticks += 1;
+        if self.take_off_ticks >= self.tiles.rules.level_complete_ticks {
+            self.level_state = LevelState::LevelComplete;
+            self.emit(GameEvent::LevelComplete);
+        }
+    }
+
+    pub fn get_level_state(&self) -> LevelState {
+        self.level_state
     }
 
     pub fn repair_capsule(&mut self) {
         let repaired = self
             .items
             .iter_mut()
-            .find(|item| item.get_kind() == Kind::Capsule)
-            .map(|item| item.as_mut_capsule())
-            .flatten()
-            .map(|c| c.repair());
-        if let Some(true) = repaired {
-            self.play_sound(Sound::Bomb)
+            .find_map(|item| match item {
+                ItemKind::Capsule(capsule) => Some(capsule),
+                _ => None,
+            })
+            .map(|c| (c.repair(), c.get_position()));
+        if let Some((true, pos)) = repaired {
+            // not moveable anymore, even if the capsule is not processed before Robbo moves again
+            if let Some(capsule) = self.items.item_at(pos) {
+                capsule.put_tile(&mut self.tiles);
+            }
+            self.play_sound_at(Sound::Capsule, pos);
+            self.emit(GameEvent::CapsuleRepaired);
         }
     }
 
+    pub fn is_capsule_repaired(&self) -> bool {
+        self.items
+            .get_items(Kind::Capsule)
+            .iter()
+            .any(|item| matches!(item, ItemKind::Capsule(capsule) if capsule.is_working()))
+    }
+
+    pub fn remaining_screws(&self) -> usize {
+        self.missing_screws.saturating_sub(self.robbo.inventory.screws)
+    }
+
     pub fn _shot(&mut self, pos: Position, direction: Direction, gun_type: GunType) {
         let dest = dest_coords(pos, direction);
         let is_dst_empty = self.tiles.is_empty(dest);
+        let shooter = (self.tiles.get_kind(pos), pos);
         if is_dst_empty {
-            let bullet: Box<dyn Item> = match gun_type {
-                GunType::Burst => Box::new(Bullet::new(direction)),
-                GunType::Solid => Box::new(LaserHead::new(direction)),
-                GunType::Blaster => Box::new(BlastHead::new(direction)),
+            let bullet: ItemKind = match gun_type {
+                GunType::Burst => Bullet::new(direction, shooter).into(),
+                GunType::Solid => LaserHead::new(direction, shooter).into(),
+                GunType::Blaster => BlastHead::new(direction, shooter).into(),
             };
             self.add_item(dest, bullet);
         } else {
-            self.destroy(dest, false);
+            self.destroy(dest, false, DeathCause::Shot(shooter.0, shooter.1));
+        }
+        match gun_type {
+            GunType::Burst => self.play_sound_at(Sound::Shot, pos),
+            GunType::Solid | GunType::Blaster => self.play_sound_at(Sound::Laser, pos),
         }
-        if let GunType::Burst = gun_type {
-            self.play_sound(Sound::Shot)
+    }
+
+    /// Asks Robbo to self-destruct, ignored when Robbo is already dead or gone,
+    /// when the level is finished or a restart is already on its way
+    pub fn request_restart(&mut self) {
+        let is_playing = self.level_state == LevelState::Playing;
+        if self.restart == Restart::Idle && self.death.is_none() && is_playing {
+            self.restart = Restart::Requested;
         }
     }
 
-    pub fn kill_robbo(&mut self) {
-        self.destroy(self.robbo.get_position(), false);
+    pub fn get_restart(&self) -> Restart {
+        self.restart
+    }
+
+    fn process_restart(&mut self) {
+        if self.restart != Restart::Requested {
+            return;
+        }
+        if self.death.is_some() {
+            // killed by something else while waiting
+            self.restart = Restart::Exploding;
+        } else if !self.robbo.is_hidden {
+            self.restart = Restart::Exploding;
+            self.kill_robbo(DeathCause::SelfDestruct);
+        }
+    }
+
+    pub fn kill_robbo(&mut self, cause: DeathCause) {
+        self.destroy(self.robbo.get_position(), false, cause);
+    }
+
+    fn report_death(&mut self, cause: DeathCause) {
+        self.death = Some(Death {
+            cause,
+            pos: self.robbo.get_position(),
+        });
+        self.emit(GameEvent::RobboKilled { cause });
+    }
+
+    /// Records an object displacement for renderers, see `get_moves`
+    pub fn record_move(&mut self, kind: Kind, from: Position, to: Position, interpolable: bool) {
+        self.moves.record(kind, from, to, interpolable)
+    }
+
+    /// Steps taken while a magnet pulls Robbo are not the player's moves
+    fn count_step(&mut self) {
+        self.stats.steps += 1;
+        if self.tiles.magnetic_force_dir.is_some() {
+            self.stats.pulls += 1;
+        }
+    }
+
+    /// Objects moved during the last tick
+    pub fn get_moves(&self) -> &[Move] {
+        self.moves.get()
+    }
+
+    pub fn get_death(&self) -> Option<Death> {
+        self.death
     }
 
     pub fn is_robbo_killed(&self) -> bool {
-        self.missing_robbo_ticks > 20
+        self.missing_robbo_ticks > self.tiles.rules.hidden_robbo_restart_ticks
+    }
+
+    pub fn get_run_record(&self) -> RunRecord {
+        RunRecord {
+            ticks: self.stats.ticks,
+            moves: self.stats.steps - self.stats.pulls,
+            bullets: self.stats.shots,
+        }
     }
 
     pub fn explode_all(&mut self) {
@@ -473,7 +897,7 @@ impl Board {
                 let pos = (x, y);
                 let kind = self.tiles.get_kind(pos);
                 if kind != Kind::Empty && kind != Kind::Wall {
-                    self.add_item(pos, Box::new(Animation::small_explosion()));
+                    self.add_item(pos, Animation::small_explosion().into());
                 }
             }
         }
@@ -483,11 +907,53 @@ impl Board {
     pub fn robbo_move_or_shot(&mut self, dir: Direction, shot: bool) {
         self.robbo.set_direction(dir, shot)
     }
+    /// Plays a sound not bound to any board position
     pub fn play_sound(&self, sound: Sound) {
-        self.sounds.play_sound(sound);
+        self.emit(GameEvent::Sound(sound, None));
+    }
+
+    pub fn play_sound_at(&self, sound: Sound, pos: Position) {
+        self.emit(GameEvent::Sound(sound, Some(pos)));
+    }
+
+    /// Plays a sound of objects acting every tick, like birds or force fields,
+    /// at most once per tick
+    pub fn play_ambient_sound_at(&mut self, sound: Sound, pos: Position) {
+        if !self.ambient_sounds.contains(&sound) {
+            self.ambient_sounds.push(sound);
+            self.play_sound_at(sound, pos);
+        }
+    }
+
+    pub fn emit(&self, event: GameEvent) {
+        self.events.push(event);
     }
 
-    pub fn get_sounds(&self) -> Vec<Sound> {
-        self.sounds.get_sounds()
+    pub fn take_events(&self) -> Vec<GameEvent> {
+        self.events.drain()
+    }
+
+    fn symbol(&self, tile: Tile) -> char {
+        match tile.get_kind() {
+            Kind::Empty => '.',
+            Kind::Bullet => ':',
+            Kind::LaserTail => '+',
+            Kind::Explosion => 'x',
+            _ => self.registry.symbol(&tile).unwrap_or('?'),
+        }
+    }
+}
+
+/// Board printed with level symbols, one row per line. Objects without a symbol
+/// are printed as `:` (bullets and laser heads), `+` (laser tails) and `x` (explosions)
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let row: String = (0..self.width)
+                .map(|x| self.symbol(self.tiles.get_or_wall((x, y))))
+                .collect();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
     }
 }
@@ -7,3 +7,10 @@ pub const COLLECTABLE: u16 = 8;
 pub const DEADLY: u16 = 16;
 
 pub const ALL_DIRS: &[types::Direction] = &[(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+pub const LIVES: usize = 8;
+
+pub const AMMO_SCORE: usize = 50;
+pub const KEY_SCORE: usize = 75;
+pub const SCREW_SCORE: usize = 100;
+pub const ENEMY_SCORE: usize = 100;
@@ -0,0 +1,105 @@
+use std::fmt;
+use types::{Kind, Position};
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// touched a deadly item at given position
+    Deadly(Kind, Position),
+    /// hit by a shot fired by given item (gun, bird or Robbo) from given position
+    Shot(Kind, Position),
+    /// hit by an explosion or a pushed item
+    Impact(Kind, Position),
+    /// walked into the front of a magnet
+    Magnet(Position),
+    SelfDestruct,
+    /// removed from the board without being killed, e.g. consumed by a blaster
+    Vanished,
+}
+
+impl DeathCause {
+    pub fn get_type(&self) -> DeathCauseType {
+        match self {
+            DeathCause::Deadly(..) => DeathCauseType::Deadly,
+            DeathCause::Shot(..) => DeathCauseType::Shot,
+            DeathCause::Impact(..) => DeathCauseType::Impact,
+            DeathCause::Magnet(_) => DeathCauseType::Magnet,
+            DeathCause::SelfDestruct => DeathCauseType::SelfDestruct,
+            DeathCause::Vanished => DeathCauseType::Vanished,
+        }
+    }
+    pub fn get_killer(&self) -> Option<(Kind, Position)> {
+        match *self {
+            DeathCause::Deadly(kind, pos)
+            | DeathCause::Shot(kind, pos)
+            | DeathCause::Impact(kind, pos) => Some((kind, pos)),
+            DeathCause::Magnet(pos) => Some((Kind::Magnet, pos)),
+            DeathCause::SelfDestruct | DeathCause::Vanished => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Death {
+    pub cause: DeathCause,
+    pub pos: Position,
+}
+
+impl fmt::Display for Death {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (x, y) = self.pos;
+        match self.cause {
+            DeathCause::Deadly(kind, (kx, ky)) => {
+                write!(f, "Robbo at {}.{} touched {:?} at {}.{}", x, y, kind, kx, ky)
+            }
+            DeathCause::Shot(kind, (kx, ky)) => {
+                write!(f, "Robbo at {}.{} was shot by {:?} from {}.{}", x, y, kind, kx, ky)
+            }
+            DeathCause::Impact(kind, (kx, ky)) => {
+                write!(f, "Robbo at {}.{} was hit by {:?} at {}.{}", x, y, kind, kx, ky)
+            }
+            DeathCause::Magnet((kx, ky)) => {
+                write!(f, "Robbo at {}.{} walked into magnet at {}.{}", x, y, kx, ky)
+            }
+            DeathCause::SelfDestruct => write!(f, "Robbo at {}.{} self-destructed", x, y),
+            DeathCause::Vanished => write!(f, "Robbo at {}.{} vanished", x, y),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCauseType {
+    Deadly,
+    Shot,
+    Impact,
+    Magnet,
+    SelfDestruct,
+    Vanished,
+}
+
+/// Flat representation of `Death` passed to JavaScript
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeathInfo {
+    pub cause: DeathCauseType,
+    pub x: i32,
+    pub y: i32,
+    pub killer_kind: Option<i32>,
+    pub killer_x: Option<i32>,
+    pub killer_y: Option<i32>,
+}
+
+impl From<Death> for DeathInfo {
+    fn from(death: Death) -> DeathInfo {
+        let killer = death.cause.get_killer();
+        DeathInfo {
+            cause: death.cause.get_type(),
+            x: death.pos.0,
+            y: death.pos.1,
+            killer_kind: killer.map(|(kind, _)| kind as i32),
+            killer_x: killer.map(|(_, pos)| pos.0),
+            killer_y: killer.map(|(_, pos)| pos.1),
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use sound::Sound;
+use std::cell::RefCell;
+use death::DeathCause;
+use types::{Kind, Position};
+use wasm_bindgen::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    Sound(Sound, Option<Position>),
+    ItemCollected(Kind, Position),
+    ItemDestroyed(Kind, Position),
+    RobboKilled { cause: DeathCause },
+    CapsuleRepaired,
+    /// Robbo entered the capsule
+    LevelFinished,
+    /// the capsule took off, see `LevelState::LevelComplete`
+    LevelComplete,
+    Teleported { from: Position, to: Position },
+    DoorOpened,
+}
+
+pub struct Events {
+    events: RefCell<Vec<GameEvent>>,
+}
+
+impl Events {
+    pub fn new() -> Events {
+        Events {
+            events: RefCell::new(Vec::new()),
+        }
+    }
+    pub fn push(&self, event: GameEvent) {
+        self.events.borrow_mut().push(event)
+    }
+    pub fn drain(&self) -> Vec<GameEvent> {
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    Sound,
+    ItemCollected,
+    ItemDestroyed,
+    RobboKilled,
+    CapsuleRepaired,
+    LevelFinished,
+    Teleported,
+    DoorOpened,
+    LevelComplete,
+}
+
+/// Flat representation of `GameEvent` passed to JavaScript,
+/// `value` holds the sound id, item kind or death cause depending on `event_type`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    pub event_type: EventType,
+    pub value: i32,
+    pub x: i32,
+    pub y: i32,
+    pub to_x: i32,
+    pub to_y: i32,
+}
+
+impl Event {
+    fn new(event_type: EventType, value: i32, (x, y): Position, (to_x, to_y): Position) -> Event {
+        Event {
+            event_type,
+            value,
+            x,
+            y,
+            to_x,
+            to_y,
+        }
+    }
+}
+
+impl From<GameEvent> for Event {
+    fn from(event: GameEvent) -> Event {
+        let none = (0, 0);
+        match event {
+            GameEvent::Sound(sound, pos) => {
+                let pos = pos.unwrap_or(none);
+                Event::new(EventType::Sound, sound as i32, pos, pos)
+            }
+            GameEvent::ItemCollected(kind, pos) => {
+                Event::new(EventType::ItemCollected, kind as i32, pos, pos)
+            }
+            GameEvent::ItemDestroyed(kind, pos) => {
+                Event::new(EventType::ItemDestroyed, kind as i32, pos, pos)
+            }
+            GameEvent::RobboKilled { cause } => {
+                Event::new(EventType::RobboKilled, cause.get_type() as i32, none, none)
+            }
+            GameEvent::CapsuleRepaired => Event::new(EventType::CapsuleRepaired, 0, none, none),
+            GameEvent::LevelFinished => Event::new(EventType::LevelFinished, 0, none, none),
+            GameEvent::LevelComplete => Event::new(EventType::LevelComplete, 0, none, none),
+            GameEvent::Teleported { from, to } => Event::new(EventType::Teleported, 0, from, to),
+            GameEvent::DoorOpened => Event::new(EventType::DoorOpened, 0, none, none),
+        }
+    }
+}
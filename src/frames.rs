@@ -0,0 +1,80 @@
+use std::rc::Rc;
+use types::Action;
+
+/// Number of game ticks each frame of a looped animation is shown by default.
+pub const DEFAULT_FRAME_DURATION: f64 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Playback {
+    Loop,
+    Once,
+}
+
+/// Animation descriptor: which atlas frames an object cycles through and how fast.
+///
+/// Time is measured in game ticks and may be fractional, so renderers running
+/// faster than the game can sample frames between ticks. At whole ticks the
+/// result is exactly what game logic sees.
+///
+/// The tiles are shared, so items created from the same definition or showing a
+/// slice of their frames do not copy them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frames {
+    tiles: Rc<[usize]>,
+    start: usize,
+    len: usize,
+    pub duration: f64,
+    pub playback: Playback,
+    pub on_finish: Option<Action>,
+}
+
+impl Frames {
+    fn new<T: Into<Rc<[usize]>>>(tiles: T, duration: f64, playback: Playback) -> Frames {
+        let tiles = tiles.into();
+        Frames {
+            len: tiles.len(),
+            tiles,
+            start: 0,
+            duration,
+            playback,
+            on_finish: None,
+        }
+    }
+    pub fn looped<T: Into<Rc<[usize]>>>(tiles: T, duration: f64) -> Frames {
+        Frames::new(tiles, duration, Playback::Loop)
+    }
+    pub fn once<T: Into<Rc<[usize]>>>(tiles: T, on_finish: Action) -> Frames {
+        Frames {
+            on_finish: Some(on_finish),
+            ..Frames::new(tiles, 1.0, Playback::Once)
+        }
+    }
+    pub fn tiles(&self) -> &[usize] {
+        &self.tiles[self.start..self.start + self.len]
+    }
+    pub fn slice(&self, start: usize, len: usize) -> Frames {
+        assert!(start + len <= self.len, "frames {}..{} out of {}", start, start + len, self.len);
+        Frames {
+            start: self.start + start,
+            len,
+            ..self.clone()
+        }
+    }
+    pub fn duration(self, duration: f64) -> Frames {
+        Frames { duration, ..self }
+    }
+    fn index(&self, time: f64) -> usize {
+        let index = (time.max(0.0) / self.duration) as usize;
+        match self.playback {
+            Playback::Loop => index % self.len,
+            Playback::Once => index.min(self.len - 1),
+        }
+    }
+    pub fn get_tile(&self, time: f64) -> usize {
+        self.tiles()[self.index(time)]
+    }
+    /// True when a `Once` animation has shown its last frame at the given age.
+    pub fn is_finished(&self, age: usize) -> bool {
+        self.playback == Playback::Once && (age + 1) as f64 >= self.len as f64 * self.duration
+    }
+}
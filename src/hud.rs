@@ -0,0 +1,35 @@
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Values shown in the status bar, frontends decide how to render them
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hud {
+    pub level: usize,
+    /// name of the level pack, levels have no names of their own
+    pub(crate) pack_name: String,
+    pub screws: usize,
+    pub keys: usize,
+    pub bullets: usize,
+    pub lives: usize,
+    pub score: usize,
+    pub capsule_repaired: bool,
+}
+
+#[wasm_bindgen]
+impl Hud {
+    #[wasm_bindgen(getter)]
+    pub fn pack_name(&self) -> String {
+        self.pack_name.clone()
+    }
+}
+
+impl fmt::Display for Hud {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "level: {:02} screws: {:02} keys: {:02} bullets: {:02}",
+            self.level, self.screws, self.keys, self.bullets
+        )
+    }
+}
@@ -1,23 +1,16 @@
-use consts;
-use tiles::Tiles; use types::{Action, Actions, Direction, Kind};
+use tiles::Tiles; use types::{Action, Actions, Direction};
 use utils::direction_by_index;
 use super::{Item, SimpleItem};
 use rand::Rng;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Butterfly {
     simple_item: SimpleItem,
 }
 
 impl Butterfly {
-    const MOVE_PROBABILITY: f64 = 1.0;
-    const RANDOM_MOVE_PROBABILITY: f64 = 0.1;
-
-    pub fn new() -> Butterfly {
-        Butterfly {
-            simple_item: SimpleItem::new(Kind::Butterfly, &[32, 33])
-                .flags(consts::DESTROYABLE | consts::DEADLY),
-        }
+    pub fn new(simple_item: SimpleItem) -> Butterfly {
+        Butterfly { simple_item }
     }
 }
 
@@ -30,10 +23,10 @@ impl Item for Butterfly {
     }
     fn tick(&mut self, tiles: &Tiles, rng: &mut dyn rand::RngCore) -> Actions {
         let neighbours = tiles.get_neighbours(self.get_position());
-        if rng.gen::<f64>() > Butterfly::MOVE_PROBABILITY {
+        if rng.gen::<f64>() > tiles.rules.butterfly_move_probability {
             return Actions::empty();
         }
-        if rng.gen::<f64>() < Butterfly::RANDOM_MOVE_PROBABILITY {
+        if rng.gen::<f64>() < tiles.rules.butterfly_random_move_probability {
             let valid_dirs = (0..4)
                 .map(direction_by_index)
                 .filter(|dir| neighbours.get(*dir).is_empty())
@@ -0,0 +1,92 @@
+use super::{Item, ItemKind, SimpleItem};
+use crate::board::Board;
+use crate::types::{Action, Actions, Direction, Kind, Position};
+use death::DeathCause;
+use sound::Sound;
+use tiles::Tiles;
+use utils::{direction_by_index, modulo};
+
+/// Barrier moving along its row. The level format notes bundled with GNU Robbo
+/// (see the top of `original.txt`) say force fields are "contained by either a wall or the
+/// edges of the level. Everything else they will consume." Here "contained" means that all
+/// fields of a row segment, bounded by walls or the edges of the level, move together one
+/// cell per tick and fields leaving the segment at one end enter it at the other one.
+/// Fields moving the other way bound the segment too, so opposite fields never consume
+/// each other.
+#[derive(Clone, Debug)]
+pub struct ForceField {
+    simple_item: SimpleItem,
+    direction: Direction,
+}
+
+impl ForceField {
+    /// `params[0]` is the direction, 0 for east and 2 for west
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> ForceField {
+        let (dx, _) = direction_by_index(params[0] as usize);
+        ForceField {
+            simple_item,
+            direction: if dx < 0 { (-1, 0) } else { (1, 0) },
+        }
+    }
+
+    fn direction_at(board: &Board, pos: Position) -> Option<Direction> {
+        match board.items.item_at(pos) {
+            Some(ItemKind::ForceField(force_field)) => Some(force_field.direction),
+            _ => None,
+        }
+    }
+
+    /// First and last column of the row segment around `pos` of fields moving in
+    /// `direction`, walls and fields moving the other way excluded
+    fn segment(board: &Board, (x, y): Position, direction: Direction) -> (i32, i32) {
+        let is_open = |x: &i32| {
+            let pos = (*x, y);
+            let opposite = ForceField::direction_at(board, pos).filter(|dir| *dir != direction);
+            board.tiles.get_kind(pos) != Kind::Wall && opposite.is_none()
+        };
+        let start = (0..x).rev().take_while(is_open).last().unwrap_or(x);
+        let end = (x + 1..board.width).take_while(is_open).last().unwrap_or(x);
+        (start, end)
+    }
+
+    /// Moves the fields of the segment of the field at `pos` in its direction. The segment
+    /// moves once per tick, as moved fields are not processed again during the tick.
+    pub fn process_force_field(board: &mut Board, pos: Position) {
+        let direction = match ForceField::direction_at(board, pos) {
+            Some(direction) => direction,
+            None => return,
+        };
+        let (start, end) = ForceField::segment(board, pos, direction);
+        let (_, y) = pos;
+        let columns: Vec<i32> = (start..=end)
+            .filter(|x| ForceField::direction_at(board, (*x, y)) == Some(direction))
+            .collect();
+        let fields: Vec<ItemKind> = columns
+            .into_iter()
+            .filter_map(|x| board.remove_at((x, y)))
+            .collect();
+        if !fields.is_empty() {
+            board.play_ambient_sound_at(Sound::ForceField, pos);
+        }
+        let len = end - start + 1;
+        for field in fields {
+            let from = field.get_position();
+            let to = (start + modulo(from.0 + direction.0 - start, len), y);
+            board.consume(to, DeathCause::Impact(Kind::ForceField, from));
+            board.record_move(Kind::ForceField, from, to, to.0 == from.0 + direction.0);
+            board.add_item(to, field);
+        }
+    }
+}
+
+impl Item for ForceField {
+    fn get_simple_item(&self) -> &SimpleItem {
+        &self.simple_item
+    }
+    fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
+        &mut self.simple_item
+    }
+    fn tick(&mut self, _tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
+        Actions::single(Action::ForceField)
+    }
+}
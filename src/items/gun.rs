@@ -1,19 +1,20 @@
+use frames::Frames;
 use consts;
 use tiles::Tiles;
-use types::{Action, Actions, Direction, Kind};
+use types::{Action, Actions, Direction};
 use utils::{direction_by_index, direction_to_index, rotate_clockwise};
 use super::{Item, SimpleItem};
 use rand::Rng;
 
 #[repr(u16)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum GunType {
     Burst = 0,
     Solid = 1,
     Blaster = 2,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Gun {
     simple_item: SimpleItem,
     shooting_dir: Direction,
@@ -25,17 +26,11 @@ pub struct Gun {
     pub disabled: bool,
 }
 impl Gun {
-    const SHOOTING_PROPABILITY: f64 = 0.075;
-    const ROTATE_PROBABILITY: f64 = 0.25;
-
-    pub fn new(params: &[u16]) -> Gun {
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> Gun {
         let is_moveable = params[3] > 0;
+        let flags = simple_item.get_flags() | if is_moveable { consts::MOVEABLE } else { 0 };
         Gun {
-            simple_item: SimpleItem::new(Kind::Gun, &[53, 54, 55, 56]).flags(if is_moveable {
-                consts::MOVEABLE
-            } else {
-                0
-            }),
+            simple_item: simple_item.flags(flags),
             shooting_dir: direction_by_index(params[0] as usize),
             moving_dir: direction_by_index(params[1] as usize),
             gun_type: match params[2] {
@@ -57,8 +52,8 @@ impl Item for Gun {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, _frame_cnt: usize) -> usize {
-        self.simple_item.tiles[direction_to_index(self.shooting_dir)]
+    fn get_frames(&self) -> Frames {
+        self.simple_item.frames.slice(direction_to_index(self.shooting_dir), 1)
     }
     fn tick(&mut self, tiles: &Tiles, rng: &mut dyn rand::RngCore) -> Actions {
         let mut actions: Vec<Action> = Vec::new();
@@ -72,7 +67,7 @@ impl Item for Gun {
             }
         }
         if (self.is_random_rotatable || self.is_rotateable)
-            && (rng.gen::<f64>() < Gun::ROTATE_PROBABILITY)
+            && (rng.gen::<f64>() < tiles.rules.gun_rotate_probability)
         {
             if self.is_random_rotatable {
                 self.shooting_dir = direction_by_index(rng.gen::<usize>() % 4);
@@ -80,7 +75,7 @@ impl Item for Gun {
                 self.shooting_dir = rotate_clockwise(self.shooting_dir);
             }
         }
-        if !self.disabled && rng.gen::<f64>() < Gun::SHOOTING_PROPABILITY {
+        if !self.disabled && rng.gen::<f64>() < tiles.rules.gun_shooting_probability {
             actions.push(match self.gun_type {
                 GunType::Solid => Action::CreateLaser(self.shooting_dir),
                 GunType::Blaster => Action::CreateBlast(self.shooting_dir),
@@ -89,12 +84,6 @@ impl Item for Gun {
         }
         Actions::new(&actions)
     }
-    fn as_mut_gun(&mut self) -> Option<&mut Gun> {
-        Some(self)
-    }
 
-    fn as_gun(&self) -> Option<&Gun> {
-        Some(self)
-    }
 }
 
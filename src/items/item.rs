@@ -1,6 +1,9 @@
-use super::{Robbo, Gun, Teleport, Capsule, Magnet, ForceField};
+use super::Robbo;
 use consts;
+use frames::{Frames, DEFAULT_FRAME_DURATION};
 use tiles::{Neighbourhood, Tile, Tiles};
+use death::DeathCause;
+use std::rc::Rc;
 use types::{Actions, Direction, Flags, Kind, Position};
 use utils::dest_coords;
 
@@ -18,12 +21,19 @@ pub trait Item: std::fmt::Debug {
         self.get_simple_item().pos
     }
     fn _get_neighbours<'tiles>(&self, tiles: &'tiles Tiles) -> Neighbourhood<'tiles> {
-        return Neighbourhood::new(tiles, self.get_position());
+        Neighbourhood::new(tiles, self.get_position())
     }
     fn get_simple_item(&self) -> &SimpleItem;
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem;
+    fn get_frames(&self) -> Frames {
+        self.get_simple_item().frames.clone()
+    }
     fn get_tile(&self, frame_cnt: usize) -> usize {
-        self.get_simple_item().get_tile(frame_cnt)
+        self.get_tile_at(frame_cnt as f64)
+    }
+    /// Tile at a possibly fractional tick, for renderers drawing between ticks.
+    fn get_tile_at(&self, time: f64) -> usize {
+        self.get_frames().get_tile(time)
     }
     fn tick(&mut self, _tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
         Actions::empty()
@@ -53,70 +63,43 @@ pub trait Item: std::fmt::Debug {
     fn destroy(&mut self) -> bool {
         false
     }
-    fn as_teleport(&self) -> Option<&Teleport> {
-        None
-    }
-    fn as_mut_capsule(&mut self) -> Option<&mut Capsule> {
-        None
-    }
-    fn as_mut_gun(&mut self) -> Option<&mut Gun> {
-        None
-    }
-    fn as_gun(&self) -> Option<&Gun> {
-        None
-    }
-    fn as_magnet(&self) -> Option<&Magnet> {
-        None
-    }
-    fn as_force_field(&self) -> Option<&ForceField> {
-        None
+    fn get_death_cause(&self) -> DeathCause {
+        DeathCause::Impact(self.get_kind(), self.get_position())
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SimpleItem {
     kind: Kind,
-    pub tiles: &'static [usize],
+    pub frames: Frames,
     flags: Flags,
     pub pos: Position,
 }
 impl SimpleItem {
-    pub fn new(kind: Kind, tiles: &'static [usize]) -> SimpleItem {
+    pub fn new<T: Into<Rc<[usize]>>>(kind: Kind, tiles: T) -> SimpleItem {
         SimpleItem {
             kind,
-            tiles,
+            frames: Frames::looped(tiles, DEFAULT_FRAME_DURATION),
             flags: 0,
             pos: (0, 0),
         }
     }
-    pub fn flags(&self, flags: Flags) -> SimpleItem {
-        SimpleItem { flags, ..*self }
+    pub fn flags(self, flags: Flags) -> SimpleItem {
+        SimpleItem { flags, ..self }
     }
-    fn get_tile(&self, frame_cnt: usize) -> usize {
-        self.tiles[frame_cnt / 2 % self.tiles.len()]
+    pub fn frames(self, frames: Frames) -> SimpleItem {
+        SimpleItem { frames, ..self }
     }
-    fn get_kind(&self) -> Kind {
+    pub fn get_kind(&self) -> Kind {
         self.kind
     }
-    fn get_flags(&self) -> Flags {
+    pub fn get_flags(&self) -> Flags {
         self.flags
     }
-    pub fn abox() -> SimpleItem {
-        SimpleItem::new(Kind::ABox, &[20]).flags(consts::MOVEABLE)
-    }
-    pub fn horizontal_laser() -> SimpleItem {
-        SimpleItem::new(Kind::HorizontalLaser, &[53])
-    }
-    pub fn vertical_laser() -> SimpleItem {
-        SimpleItem::new(Kind::VerticalLaser, &[53])
-    }
     pub fn laser_tail((dx, _dy): Direction) -> SimpleItem {
-        SimpleItem::new(Kind::LaserTail, if dx != 0 { &[36, 37] } else { &[38, 39] })
+        SimpleItem::new(Kind::LaserTail, if dx != 0 { &[36, 37][..] } else { &[38, 39][..] })
             .flags(consts::UNDESTROYABLE)
     }
-    pub fn questionmark() -> SimpleItem {
-        SimpleItem::new(Kind::Questionmark, &[12]).flags(consts::DESTROYABLE | consts::MOVEABLE)
-    }
 }
 
 impl Item for SimpleItem {
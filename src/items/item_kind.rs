@@ -0,0 +1,129 @@
+use super::{
+    Animation, Bear, Bird, BlastHead, Bomb, Bullet, Butterfly, Capsule, Door, ForceField, Gun,
+    Item, LaserHead, Magnet, PushBox, Robbo, SimpleItem, Teleport,
+};
+use death::DeathCause;
+use frames::Frames;
+use tiles::{Neighbourhood, Tiles};
+use types::{Actions, Direction, Flags, Kind, Position};
+
+/// Every item placed on the board, dispatched with a `match` instead of a trait object
+#[derive(Clone, Debug)]
+pub enum ItemKind {
+    Simple(SimpleItem),
+    Animation(Animation),
+    Bear(Bear),
+    Bird(Bird),
+    BlastHead(BlastHead),
+    Bomb(Bomb),
+    Bullet(Bullet),
+    Butterfly(Butterfly),
+    Capsule(Capsule),
+    Door(Door),
+    ForceField(ForceField),
+    Gun(Gun),
+    LaserHead(LaserHead),
+    Magnet(Magnet),
+    PushBox(PushBox),
+    Teleport(Teleport),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $item:ident => $body:expr) => {
+        match $self {
+            ItemKind::Simple($item) => $body,
+            ItemKind::Animation($item) => $body,
+            ItemKind::Bear($item) => $body,
+            ItemKind::Bird($item) => $body,
+            ItemKind::BlastHead($item) => $body,
+            ItemKind::Bomb($item) => $body,
+            ItemKind::Bullet($item) => $body,
+            ItemKind::Butterfly($item) => $body,
+            ItemKind::Capsule($item) => $body,
+            ItemKind::Door($item) => $body,
+            ItemKind::ForceField($item) => $body,
+            ItemKind::Gun($item) => $body,
+            ItemKind::LaserHead($item) => $body,
+            ItemKind::Magnet($item) => $body,
+            ItemKind::PushBox($item) => $body,
+            ItemKind::Teleport($item) => $body,
+        }
+    };
+}
+
+macro_rules! from_item {
+    ($($variant:ident),*) => {
+        $(
+            impl From<$variant> for ItemKind {
+                fn from(item: $variant) -> ItemKind {
+                    ItemKind::$variant(item)
+                }
+            }
+        )*
+    };
+}
+
+from_item!(
+    Animation, Bear, Bird, BlastHead, Bomb, Bullet, Butterfly, Capsule, Door, ForceField, Gun,
+    LaserHead, Magnet, PushBox, Teleport
+);
+
+impl From<SimpleItem> for ItemKind {
+    fn from(item: SimpleItem) -> ItemKind {
+        ItemKind::Simple(item)
+    }
+}
+
+impl Item for ItemKind {
+    fn put_tile(&self, tiles: &mut Tiles) {
+        dispatch!(self, item => item.put_tile(tiles))
+    }
+    fn set_position(&mut self, pos: Position) {
+        dispatch!(self, item => item.set_position(pos))
+    }
+    fn get_position(&self) -> Position {
+        dispatch!(self, item => item.get_position())
+    }
+    fn _get_neighbours<'tiles>(&self, tiles: &'tiles Tiles) -> Neighbourhood<'tiles> {
+        dispatch!(self, item => item._get_neighbours(tiles))
+    }
+    fn get_simple_item(&self) -> &SimpleItem {
+        dispatch!(self, item => item.get_simple_item())
+    }
+    fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
+        dispatch!(self, item => item.get_simple_item_mut())
+    }
+    fn get_frames(&self) -> Frames {
+        dispatch!(self, item => item.get_frames())
+    }
+    fn get_tile(&self, frame_cnt: usize) -> usize {
+        dispatch!(self, item => item.get_tile(frame_cnt))
+    }
+    fn get_tile_at(&self, time: f64) -> usize {
+        dispatch!(self, item => item.get_tile_at(time))
+    }
+    fn tick(&mut self, tiles: &Tiles, rng: &mut dyn rand::RngCore) -> Actions {
+        dispatch!(self, item => item.tick(tiles, rng))
+    }
+    fn _mv(&mut self, dir: Direction, tiles: &mut Tiles) -> bool {
+        dispatch!(self, item => item._mv(dir, tiles))
+    }
+    fn get_kind(&self) -> Kind {
+        dispatch!(self, item => item.get_kind())
+    }
+    fn get_flags(&self) -> Flags {
+        dispatch!(self, item => item.get_flags())
+    }
+    fn enter(&mut self, robbo: &mut Robbo, direction: Direction) -> Actions {
+        dispatch!(self, item => item.enter(robbo, direction))
+    }
+    fn pushed(&mut self, direction: Direction) {
+        dispatch!(self, item => item.pushed(direction))
+    }
+    fn destroy(&mut self) -> bool {
+        dispatch!(self, item => item.destroy())
+    }
+    fn get_death_cause(&self) -> DeathCause {
+        dispatch!(self, item => item.get_death_cause())
+    }
+}
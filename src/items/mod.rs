@@ -1,38 +1,47 @@
 mod butterfly;
+mod force_field;
 mod gun;
 mod item;
+mod item_kind;
 mod robbo;
 mod teleport;
 
 pub use self::butterfly::Butterfly;
+pub use self::force_field::ForceField;
 pub use self::gun::{Gun, GunType};
 pub use self::item::{Item, SimpleItem};
-pub use self::robbo::{Inventory, Robbo};
+pub use self::item_kind::ItemKind;
+pub use self::robbo::Robbo;
 pub use self::teleport::Teleport;
-use crate::board::Board;
 use rand::Rng;
+use frames::Frames;
+use std::rc::Rc;
 use consts;
 use sound::Sound;
 use tiles::Tiles;
+use events::GameEvent;
+use death::DeathCause;
 use types::{Action, Actions, Direction, Kind, Position};
 use utils::{
     dest_coords, direction_by_index, reverse_direction, rotate_clockwise, rotate_counter_clockwise,
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Capsule {
     simple_item: SimpleItem,
     is_working: bool,
 }
 
 impl Capsule {
-    pub fn new() -> Capsule {
+    pub fn new(simple_item: SimpleItem) -> Capsule {
         Capsule {
-            simple_item: SimpleItem::new(Kind::Capsule, &[17, 18])
-                .flags(consts::MOVEABLE | consts::UNDESTROYABLE),
+            simple_item,
             is_working: false,
         }
     }
+    pub fn is_working(&self) -> bool {
+        self.is_working
+    }
     pub fn repair(&mut self) -> bool {
         let was_not_working = !self.is_working;
         self.is_working = true;
@@ -47,117 +56,39 @@ impl Item for Capsule {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, frame_cnt: usize) -> usize {
+    fn get_frames(&self) -> Frames {
         if self.is_working {
-            self.simple_item.get_tile(frame_cnt)
+            self.simple_item.frames.clone()
         } else {
-            self.simple_item.tiles[0]
+            self.simple_item.frames.slice(0, 1)
         }
     }
     fn get_flags(&self) -> u16 {
         let mut flags = self.get_simple_item().get_flags();
         if self.is_working {
-            flags = flags & !consts::MOVEABLE;
+            flags &= !consts::MOVEABLE;
         }
         flags
     }
-    fn as_mut_capsule(&mut self) -> Option<&mut Capsule> {
-        Some(self)
-    }
     fn enter(&mut self, _robbo: &mut Robbo, _direction: Direction) -> Actions {
         if self.is_working {
-            Actions::new(&[Action::NextLevel])
+            Actions::new(&[Action::EnterCapsule(self.get_position())])
         } else {
             Actions::empty()
         }
     }
 }
 
-#[derive(Debug)]
-pub struct ForceField {
-    simple_item: SimpleItem,
-    pub direction: u16,
-}
-
-impl ForceField {
-    pub fn new(params: &[u16]) -> ForceField {
-        ForceField {
-            simple_item: SimpleItem::new(Kind::ForceField, &[45, 57]).flags(consts::DESTROYABLE),
-            direction: params[0],
-        }
-    }
-    pub fn process_force_field(board: &mut Board, pos: Position) {
-        let (x, y) = pos;
-        let mut wall_x1 = x;
-        let mut wall_x2 = x;
-        while board.tiles.get_kind((wall_x1 - 1, y)) != Kind::Wall {
-            wall_x1 -= 1;
-        }
-        while board.tiles.get_kind((wall_x2, y)) != Kind::Wall {
-            wall_x2 += 1;
-        }
-
-        let ff_dir = board
-            .items
-            .item_at((x, y))
-            .unwrap()
-            .as_force_field()
-            .unwrap()
-            .direction;
-
-        let (mut x, end_x, step) = if ff_dir == 0 {
-            (wall_x1, wall_x2, 1)
-        } else {
-            (wall_x2 - 1, wall_x1 - 1, -1)
-        };
-
-        let tmp = if board.tiles.get_kind((x, y)) == Kind::ForceField {
-            board.remove_at((x, y))
-        } else {
-            None
-        };
-        x += step;
-
-        while x != end_x {
-            if board.tiles.get_kind((x, y)) == Kind::ForceField {
-                board.remove_at((x - step, y));
-                if let Some(item) = board.remove_at((x, y)) {
-                    board.add_item((x - step, y), item);
-                }
-            }
-            x += step;
-        }
-        if let Some(tmp) = tmp {
-            board.add_item((end_x - step, y), tmp);
-        }
-    }
-}
-
-impl Item for ForceField {
-    fn get_simple_item(&self) -> &SimpleItem {
-        &self.simple_item
-    }
-    fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
-        &mut self.simple_item
-    }
-    fn as_force_field(&self) -> Option<&ForceField> {
-        Some(self)
-    }
-    fn tick(&mut self, _tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
-        Actions::single(Action::ForceField)
-    }
-}
-
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Magnet {
     simple_item: SimpleItem,
     dir: usize,
 }
 impl Magnet {
-    pub fn new(params: &[u16]) -> Magnet {
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> Magnet {
         Magnet {
-            simple_item: SimpleItem::new(Kind::Magnet, &[0, 72, 1, 73]),
-            dir: params[0] as usize,
+            simple_item,
+            dir: params[0] as usize % 4,
         }
     }
     pub fn get_magnetic_force_dir(&self) -> Direction {
@@ -171,22 +102,19 @@ impl Item for Magnet {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, _frame_cnt: usize) -> usize {
-        self.simple_item.tiles[self.dir]
-    }
-    fn as_magnet(&self) -> Option<&Magnet> {
-        Some(self)
+    fn get_frames(&self) -> Frames {
+        self.simple_item.frames.slice(self.dir, 1)
     }
     fn enter(&mut self, _robbo: &mut Robbo, dir: Direction) -> Actions {
         if reverse_direction(direction_by_index(self.dir)) == dir {
-            Actions::single(Action::KillRobbo)
+            Actions::single(Action::KillRobbo(DeathCause::Magnet(self.get_position())))
         } else {
             Actions::empty()
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bird {
     simple_item: SimpleItem,
     moving_dir: Direction,
@@ -194,10 +122,9 @@ pub struct Bird {
     pub is_shooting: bool,
 }
 impl Bird {
-    pub fn new(params: &[u16]) -> Bird {
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> Bird {
         Bird {
-            simple_item: SimpleItem::new(Kind::Bird, &[15, 16])
-                .flags(consts::DESTROYABLE | consts::DEADLY),
+            simple_item,
             moving_dir: direction_by_index(params[0] as usize),
             shoting_dir: direction_by_index(params[1] as usize),
             is_shooting: params[2] > 0,
@@ -216,27 +143,33 @@ impl Item for Bird {
         let neighbours = tiles.get_neighbours(self.get_position());
         let mut actions = Actions::empty();
         if neighbours.get(self.moving_dir).is_empty() {
-            return Actions::single(Action::RelMove(self.moving_dir));
+            return Actions::new(&[
+                Action::RelMove(self.moving_dir),
+                Action::PlayAmbientSound(Sound::Bird),
+            ]);
         } else {
             let (dx, dy) = self.moving_dir;
             self.moving_dir = (-dx, -dy);
+            actions.push(Action::PlayAmbientSound(Sound::Bird));
         }
-        if self.is_shooting && rng.gen::<f32>() < 0.1 {
+        // birds that never shoot draw nothing, an f32 draw keeps the random stream
+        // the same as before the rules were configurable
+        if self.is_shooting && rng.gen::<f32>() < tiles.rules.bird_shooting_probability as f32 {
             actions.push(Action::CreateBullet(self.shoting_dir));
         }
         actions
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bear {
     simple_item: SimpleItem,
     moving_dir: Direction,
 }
 impl Bear {
-    pub fn new(kind: Kind, params: &[u16], tiles: &'static [usize]) -> Bear {
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> Bear {
         Bear {
-            simple_item: SimpleItem::new(kind, tiles).flags(consts::DESTROYABLE | consts::DEADLY),
+            simple_item,
             moving_dir: direction_by_index(params[0] as usize),
         }
     }
@@ -274,16 +207,16 @@ impl Item for Bear {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Door {
     simple_item: SimpleItem,
     open: bool,
 }
 impl Door {
-    pub fn new() -> Door {
+    pub fn new(simple_item: SimpleItem) -> Door {
         Door {
             open: false,
-            simple_item: SimpleItem::new(Kind::Door, &[9]),
+            simple_item,
         }
     }
 }
@@ -299,7 +232,10 @@ impl Item for Door {
             robbo.inventory.keys -= 1;
             robbo.inventory.show();
             self.open = true;
-            return Actions::single(Action::PlaySound(Sound::Door));
+            return Actions::new(&[
+                Action::PlaySound(Sound::Door),
+                Action::Emit(GameEvent::DoorOpened),
+            ]);
         }
         Actions::empty()
     }
@@ -312,16 +248,20 @@ impl Item for Door {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bullet {
     simple_item: SimpleItem,
     direction: Direction,
+    shooter: (Kind, Position),
 }
 impl Bullet {
-    pub fn new(direction: Direction) -> Bullet {
+    pub const TILES: &'static [usize] = &[36, 37, 38, 39];
+
+    pub fn new(direction: Direction, shooter: (Kind, Position)) -> Bullet {
         Bullet {
             direction,
-            simple_item: SimpleItem::new(Kind::Bullet, &[36, 37, 38, 39])
+            shooter,
+            simple_item: SimpleItem::new(Kind::Bullet, Bullet::TILES)
                 .flags(consts::UNDESTROYABLE),
         }
     }
@@ -333,9 +273,12 @@ impl Item for Bullet {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, frame_cnt: usize) -> usize {
+    fn get_death_cause(&self) -> DeathCause {
+        DeathCause::Shot(self.shooter.0, self.shooter.1)
+    }
+    fn get_frames(&self) -> Frames {
         let (kx, _ky) = self.direction;
-        self.simple_item.tiles[(if kx != 0 { 0 } else { 2 }) + (frame_cnt % 2)]
+        self.simple_item.frames.slice(if kx != 0 { 0 } else { 2 }, 2).duration(1.0)
     }
     fn tick(&mut self, tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
         let neighbours = tiles.get_neighbours(self.get_position());
@@ -353,16 +296,16 @@ impl Item for Bullet {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PushBox {
     simple_item: SimpleItem,
     direction: Direction,
 }
 impl PushBox {
-    pub fn new() -> PushBox {
+    pub fn new(simple_item: SimpleItem) -> PushBox {
         PushBox {
             direction: (0, 0),
-            simple_item: SimpleItem::new(Kind::ABox, &[6]).flags(consts::MOVEABLE),
+            simple_item,
         }
     }
 }
@@ -392,19 +335,21 @@ impl Item for PushBox {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct LaserHead {
     simple_item: SimpleItem,
     direction: Direction,
     moving_back: bool,
+    shooter: (Kind, Position),
 }
 impl LaserHead {
-    pub fn new(direction: Direction) -> LaserHead {
+    pub fn new(direction: Direction, shooter: (Kind, Position)) -> LaserHead {
         LaserHead {
-            simple_item: SimpleItem::new(Kind::Bullet, &[36, 37, 38, 39])
+            simple_item: SimpleItem::new(Kind::Bullet, Bullet::TILES)
                 .flags(consts::UNDESTROYABLE),
             direction,
             moving_back: false,
+            shooter,
         }
     }
 }
@@ -415,9 +360,12 @@ impl Item for LaserHead {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, frame_cnt: usize) -> usize {
+    fn get_death_cause(&self) -> DeathCause {
+        DeathCause::Shot(self.shooter.0, self.shooter.1)
+    }
+    fn get_frames(&self) -> Frames {
         let (kx, _ky) = self.direction;
-        self.simple_item.tiles[(if kx != 0 { 0 } else { 2 }) + (frame_cnt % 2)]
+        self.simple_item.frames.slice(if kx != 0 { 0 } else { 2 }, 2).duration(1.0)
     }
     fn tick(&mut self, tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
         let neighbours = tiles.get_neighbours(self.get_position());
@@ -444,16 +392,20 @@ impl Item for LaserHead {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct BlastHead {
     simple_item: SimpleItem,
     direction: Direction,
+    shooter: (Kind, Position),
 }
 impl BlastHead {
-    pub fn new(direction: Direction) -> BlastHead {
+    pub const TILES: &'static [usize] = &[84];
+
+    pub fn new(direction: Direction, shooter: (Kind, Position)) -> BlastHead {
         BlastHead {
-            simple_item: SimpleItem::new(Kind::Bullet, &[84]).flags(consts::UNDESTROYABLE),
+            simple_item: SimpleItem::new(Kind::Bullet, BlastHead::TILES).flags(consts::UNDESTROYABLE),
             direction,
+            shooter,
         }
     }
 }
@@ -464,6 +416,9 @@ impl Item for BlastHead {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
+    fn get_death_cause(&self) -> DeathCause {
+        DeathCause::Shot(self.shooter.0, self.shooter.1)
+    }
     fn tick(&mut self, tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
         let pos = self.get_position();
         let dst_pos = dest_coords(pos, self.direction);
@@ -482,19 +437,23 @@ impl Item for BlastHead {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Animation {
     simple_item: SimpleItem,
-    frame: usize,
-    final_action: Action,
+    age: usize,
 }
 
 impl Animation {
+    pub const EXPLOSION: &'static [usize] = &[50, 51, 52];
+    pub const BLAST_TAIL: &'static [usize] = &[85, 86, 86, 86, 85, 84];
+
     pub fn new(kind: Kind, tiles: &'static [usize], final_action: Action) -> Animation {
+        let tiles: Rc<[usize]> = tiles.into();
         Animation {
-            simple_item: SimpleItem::new(kind, tiles).flags(consts::UNDESTROYABLE),
-            frame: 0,
-            final_action,
+            simple_item: SimpleItem::new(kind, tiles.clone())
+                .flags(consts::UNDESTROYABLE)
+                .frames(Frames::once(tiles, final_action)),
+            age: 0,
         }
     }
     pub fn small_explosion() -> Animation {
@@ -507,17 +466,25 @@ impl Animation {
             Action::SpawnRobbo,
         )
     }
+    /// Capsule with Robbo inside, flashing and fading away
+    pub fn capsule_take_off() -> Animation {
+        Animation::new(
+            Kind::Capsule,
+            &[17, 18, 17, 18, 17, 18, 50, 51, 52],
+            Action::AutoRemove,
+        )
+    }
     pub fn kill_robbo() -> Animation {
         Animation::new(Kind::Explosion, &[52, 51, 50], Action::ExplodeAll)
     }
     pub fn teleport_robbo() -> Animation {
-        Animation::new(Kind::Explosion, &[50, 51, 52], Action::SpawnRobbo)
+        Animation::new(Kind::Explosion, Animation::EXPLOSION, Action::SpawnRobbo)
     }
     pub fn question_mark_explosion() -> Animation {
-        Animation::new(Kind::Explosion, &[50, 51, 52], Action::SpawnRandomItem)
+        Animation::new(Kind::Explosion, Animation::EXPLOSION, Action::SpawnRandomItem)
     }
     pub fn blast_tail() -> Animation {
-        Animation::new(Kind::Bullet, &[85, 86, 86, 86, 85, 84], Action::AutoRemove)
+        Animation::new(Kind::Bullet, Animation::BLAST_TAIL, Action::AutoRemove)
     }
 }
 
@@ -528,17 +495,19 @@ impl Item for Animation {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, _frame_cnt: usize) -> usize {
-        self.simple_item.tiles[self.frame]
+    fn get_tile_at(&self, time: f64) -> usize {
+        // one-shot animations run on their own clock, starting when spawned
+        self.get_frames().get_tile(self.age as f64 + time.fract())
     }
     fn tick(&mut self, _tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
-        let actions = if self.frame < self.simple_item.tiles.len() - 1 {
-            self.frame += 1;
+        let frames = self.get_frames();
+        if !frames.is_finished(self.age) {
+            self.age += 1;
             Actions::empty()
         } else {
-            Actions::new(&[Action::AutoRemove, self.final_action])
-        };
-        actions
+            let on_finish = frames.on_finish.unwrap_or(Action::AutoRemove);
+            Actions::new(&[Action::AutoRemove, on_finish])
+        }
     }
 }
 
@@ -550,17 +519,16 @@ pub enum BombState {
     Final,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bomb {
     simple_item: SimpleItem,
     state: BombState,
 }
 
 impl Bomb {
-    pub fn new() -> Bomb {
+    pub fn new(simple_item: SimpleItem) -> Bomb {
         Bomb {
-            simple_item: SimpleItem::new(Kind::Bomb, &[8])
-                .flags(consts::DESTROYABLE | consts::MOVEABLE),
+            simple_item,
             state: BombState::Ready,
         }
     }
@@ -599,9 +567,10 @@ impl Item for Bomb {
         }
     }
     fn destroy(&mut self) -> bool {
-        if self.state == BombState::Ready {
+        let ignited = self.state == BombState::Ready;
+        if ignited {
             self.state = BombState::Ignited;
         }
-        true
+        ignited
     }
 }
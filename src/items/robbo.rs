@@ -1,16 +1,18 @@
 use super::{Item, SimpleItem};
-use consts::DESTROYABLE;
+use frames::Frames;
+use consts::{self, DESTROYABLE};
 use log::log;
 use tiles::Tiles;
 use types::{Action, Actions, Direction, Kind};
 use utils::direction_to_index;
 use sound::Sound;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Inventory {
     pub keys: usize,
     pub bullets: usize,
     pub screws: usize,
+    pub score: usize,
 }
 
 impl Inventory {
@@ -19,20 +21,24 @@ impl Inventory {
             keys: 0,
             bullets: 0,
             screws: 0,
+            score: 0,
         }
     }
     pub fn collect(&mut self, kind: Kind) -> Actions {
         match kind {
             Kind::Ammo => {
                 self.bullets += 9;
+                self.score += consts::AMMO_SCORE;
                 Actions::single(Action::PlaySound(Sound::Ammo))
             }
             Kind::Key => {
                 self.keys += 1;
+                self.score += consts::KEY_SCORE;
                 Actions::single(Action::PlaySound(Sound::Key))
             }
             Kind::Screw => {
                 self.screws += 1;
+                self.score += consts::SCREW_SCORE;
                 Actions::single(Action::PlaySound(Sound::Screw))
             }
             _ => Actions::empty()
@@ -43,7 +49,7 @@ impl Inventory {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Robbo {
     simple_item: SimpleItem,
     direction: Direction,
@@ -54,13 +60,15 @@ pub struct Robbo {
     pub is_killed: bool,
 }
 impl Robbo {
+    pub const TILES: &'static [usize] = &[60, 61, 62, 63, 64, 65, 66, 67];
+
     pub fn new() -> Robbo {
         Robbo {
             direction: (-1, 0),
             shot_direction: None,
             moving_direction: None,
             inventory: Inventory::new(),
-            simple_item: SimpleItem::new(Kind::Robbo, &[60, 61, 62, 63, 64, 65, 66, 67])
+            simple_item: SimpleItem::new(Kind::Robbo, Robbo::TILES)
                 .flags(DESTROYABLE),
             is_hidden: true,
             is_killed: false,
@@ -109,16 +117,15 @@ impl Item for Robbo {
     fn get_simple_item_mut(&mut self) -> &mut SimpleItem {
         &mut self.simple_item
     }
-    fn get_tile(&self, frame_cnt: usize) -> usize {
-        let index = direction_to_index(self.direction) * 2;
-        self.simple_item.tiles[index + frame_cnt / 2 % 2]
+    fn get_frames(&self) -> Frames {
+        self.simple_item.frames.slice(direction_to_index(self.direction) * 2, 2)
     }
     fn tick(&mut self, tiles: &Tiles, _rng: &mut dyn rand::RngCore) -> Actions {
         if self.is_hidden {
             return Actions::empty();
         }
         if let Some(dir) = tiles.magnetic_force_dir {
-            return Actions::new(&[Action::RobboMove(dir)]);
+            return Actions::single(Action::RobboMove(dir));
         }
         if let Some(direction) = self.shot_direction {
             if self.inventory.bullets > 0 {
@@ -130,6 +137,6 @@ impl Item for Robbo {
         if let Some(dir) = self.moving_direction {
             return Actions::new(&[Action::RobboMove(dir)]);
         }
-        return Actions::empty()
+        Actions::empty()
     }
 }
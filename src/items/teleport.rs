@@ -1,10 +1,11 @@
-use super::{Animation, Item, Robbo, SimpleItem};
+use super::{Animation, Item, ItemKind, Robbo, SimpleItem};
 use crate::board::Board;
 use crate::types::{Action, Actions, Direction, Kind, Position};
+use events::GameEvent;
 use sound::Sound;
 use utils::{dest_coords, rotate_clockwise, rotate_counter_clockwise};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Teleport {
     simple_item: SimpleItem,
     pub group: u16,
@@ -12,26 +13,31 @@ pub struct Teleport {
 }
 
 impl Teleport {
-    pub fn new(params: &[u16]) -> Teleport {
+    pub fn new(simple_item: SimpleItem, params: &[u16]) -> Teleport {
         Teleport {
-            simple_item: SimpleItem::new(Kind::Teleport, &[48, 49]),
+            simple_item,
             group: params[0],
             position_in_group: params[1],
         }
     }
+    /// Moves Robbo next to the next teleport of the group with a free cell,
+    /// returns false if there is none and Robbo stays in place
     pub fn teleport_robbo(
         board: &mut Board,
         group: u16,
         position_in_group: u16,
         direction: Direction,
-    ) {
+    ) -> bool {
         let dest_teleport_positions = {
             let mut teleports = {
                 board
                     .items
                     .get_items(Kind::Teleport)
                     .iter()
-                    .map(|item| item.as_teleport().unwrap())
+                    .filter_map(|item| match item {
+                        ItemKind::Teleport(teleport) => Some(teleport),
+                        _ => None,
+                    })
                     .filter(|v| v.group == group)
                     .collect::<Vec<&Teleport>>()
             };
@@ -54,7 +60,7 @@ impl Teleport {
 
         for teleport_pos in dest_teleport_positions {
             let mut dir = direction;
-            let cc = dir.0 != 0; // hack for level 16
+            let cc = board.tiles.rules.teleport_horizontal_counter_clockwise && dir.0 != 0;
             for _ in 0..4 {
                 let dest_robbo_pos = dest_coords(teleport_pos, dir);
                 if board
@@ -63,11 +69,17 @@ impl Teleport {
                     .map(|v| v.is_empty())
                     .unwrap_or(false)
                 {
+                    let robbo_pos = board.robbo.get_position();
                     board.robbo.hide(&mut board.tiles);
                     board.robbo.set_position(dest_robbo_pos);
-                    board.play_sound(Sound::Teleport);
-                    board.add_item(dest_robbo_pos, Box::new(Animation::teleport_robbo()));
-                    return;
+                    board.record_move(Kind::Robbo, robbo_pos, dest_robbo_pos, false);
+                    board.emit(GameEvent::Teleported {
+                        from: robbo_pos,
+                        to: dest_robbo_pos,
+                    });
+                    board.play_sound_at(Sound::Teleport, dest_robbo_pos);
+                    board.add_item(dest_robbo_pos, Animation::teleport_robbo().into());
+                    return true;
                 }
                 dir = if cc {
                     rotate_counter_clockwise(dir)
@@ -76,6 +88,7 @@ impl Teleport {
                 }
             }
         }
+        false
     }
 }
 
@@ -93,7 +106,4 @@ impl Item for Teleport {
             direction,
         )])
     }
-    fn as_teleport(&self) -> Option<&Teleport> {
-        Some(self)
-    }
 }
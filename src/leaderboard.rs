@@ -0,0 +1,119 @@
+use storage::Storage;
+use wasm_bindgen::prelude::*;
+
+/// Result of a finished level run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunRecord {
+    pub ticks: usize,
+    pub moves: usize,
+    pub bullets: usize,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    pub value: usize,
+    replay_key: String,
+}
+
+#[wasm_bindgen]
+impl LeaderboardEntry {
+    #[wasm_bindgen(getter)]
+    pub fn replay_key(&self) -> String {
+        self.replay_key.clone()
+    }
+}
+
+impl LeaderboardEntry {
+    pub fn new(value: usize, replay_key: &str) -> LeaderboardEntry {
+        LeaderboardEntry {
+            value,
+            replay_key: String::from(replay_key),
+        }
+    }
+    fn improve(entry: &mut Option<LeaderboardEntry>, value: usize, replay_key: &str) -> bool {
+        if entry.as_ref().map(|e| value < e.value).unwrap_or(true) {
+            *entry = Some(LeaderboardEntry::new(value, replay_key));
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Best results of a single level of a pack
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub best_time: Option<LeaderboardEntry>,
+    pub fewest_moves: Option<LeaderboardEntry>,
+    pub fewest_bullets: Option<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    fn key(pack: &str, level: usize) -> String {
+        format!("leaderboard_{}_{}", pack, level)
+    }
+
+    pub fn replay_key(pack: &str, level: usize, record: &RunRecord) -> String {
+        format!(
+            "replay_{}_{}_{}_{}_{}",
+            pack, level, record.ticks, record.moves, record.bullets
+        )
+    }
+
+    pub fn load(storage: &dyn Storage, pack: &str, level: usize) -> Leaderboard {
+        storage
+            .get(&Leaderboard::key(pack, level))
+            .map(|data| Leaderboard::parse(&data))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, pack: &str, level: usize) {
+        storage.set(&Leaderboard::key(pack, level), &self.serialize());
+    }
+
+    /// Updates every table the record beats, returns true if any of them changed
+    pub fn submit(&mut self, record: &RunRecord, replay_key: &str) -> bool {
+        let time = LeaderboardEntry::improve(&mut self.best_time, record.ticks, replay_key);
+        let moves = LeaderboardEntry::improve(&mut self.fewest_moves, record.moves, replay_key);
+        let bullets = LeaderboardEntry::improve(&mut self.fewest_bullets, record.bullets, replay_key);
+        time || moves || bullets
+    }
+
+    fn serialize(&self) -> String {
+        [
+            ("best_time", &self.best_time),
+            ("fewest_moves", &self.fewest_moves),
+            ("fewest_bullets", &self.fewest_bullets),
+        ]
+        .iter()
+        .filter_map(|(name, entry)| {
+            entry
+                .as_ref()
+                .map(|e| format!("{}.{}.{}", name, e.value, e.replay_key))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+    }
+
+    fn parse(data: &str) -> Leaderboard {
+        let mut leaderboard = Leaderboard::default();
+        for line in data.lines() {
+            let parts = line.splitn(3, '.').collect::<Vec<&str>>();
+            if parts.len() != 3 {
+                continue;
+            }
+            let entry = parts[1]
+                .parse::<usize>()
+                .ok()
+                .map(|value| LeaderboardEntry::new(value, parts[2]));
+            match parts[0] {
+                "best_time" => leaderboard.best_time = entry,
+                "fewest_moves" => leaderboard.fewest_moves = entry,
+                "fewest_bullets" => leaderboard.fewest_bullets = entry,
+                _ => (),
+            }
+        }
+        leaderboard
+    }
+}
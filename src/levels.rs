@@ -1,96 +1,174 @@
 use log::log;
+use rules::RuleSet;
 use std::collections::HashMap;
+use std::fmt;
+use utils::fnv1a;
 
 type AdditionalMap = HashMap<(usize, usize), Vec<u16>>;
 
 pub struct LevelSet {
     pub name: String,
+    pub notes: String,
+    pub rules: RuleSet,
     pub levels: Vec<Level>,
 }
 
+/// Largest accepted level width and height
+pub const MAX_LEVEL_SIZE: i32 = 256;
+
+/// Level pack syntax error, `line` is 1-based
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: &str) -> ParseError {
+        ParseError {
+            line,
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Lines of a pack, numbered from 1
+struct Lines<'a> {
+    lines: std::iter::Enumerate<std::str::Split<'a, char>>,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(data: &'a str) -> Lines<'a> {
+        Lines {
+            lines: data.split('\n').enumerate(),
+            line: 0,
+        }
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.lines.next().map(|(index, line)| {
+            self.line = index + 1;
+            line
+        })
+    }
+
+    /// Value line following a section header
+    fn value(&mut self, section: &str) -> Result<&'a str, ParseError> {
+        let line = self.line;
+        self.next()
+            .ok_or_else(|| ParseError::new(line, &format!("missing value of {}", section)))
+    }
+
+    fn number<T: std::str::FromStr>(&self, value: &str) -> Result<T, ParseError> {
+        value
+            .trim()
+            .parse()
+            .map_err(|_| ParseError::new(self.line, &format!("invalid number: {}", value)))
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, message)
+    }
+}
+
 impl LevelSet {
-    pub fn parse(data: &str) -> LevelSet {
+    pub fn parse(data: &str) -> Result<LevelSet, ParseError> {
         let mut levels: Vec<Level> = Vec::new();
         let mut level_set_name: Option<&str> = None;
+        let mut rules = RuleSet::default();
         let mut default_level_color: String = String::from("000000");
         let mut collecting_data: bool = false;
+        let mut notes: Vec<&str> = Vec::new();
+        let mut collecting_notes: bool = false;
         let mut current_level = Level::new();
-        let mut lines = data.split('\n');
-        loop {
-            let line = lines.next();
-            if line.is_none() {
-                break;
-            }
-            let line = line.unwrap();
+        let mut lines = Lines::new(data);
+        while let Some(line) = lines.next() {
             if line.starts_with('[') {
                 collecting_data = false;
+                collecting_notes = false;
             }
             match line {
-                "[level]" => current_level.number = lines.next().unwrap().parse().unwrap(),
+                "[level]" => {
+                    let number = lines.value(line)?;
+                    current_level.number = lines.number(number)?;
+                }
                 "[name]" => {
-                    level_set_name = Some(lines.next().unwrap());
+                    level_set_name = Some(lines.value(line)?);
+                }
+                "[rules]" => {
+                    let name = lines.value(line)?;
+                    rules = RuleSet::by_name(name).unwrap_or_else(|| {
+                        log!("unknown rules: {}, using {}", name, rules.name);
+                        rules
+                    });
                 }
                 "[colour]" => {
-                    current_level.color = String::from(lines.next().unwrap());
+                    current_level.color = String::from(lines.value(line)?);
                 }
                 "[default_level_colour]" => {
-                    default_level_color = String::from(lines.next().unwrap());
+                    default_level_color = String::from(lines.value(line)?);
                 }
                 "[size]" => {
-                    let mut it = lines
-                        .next()
-                        .unwrap()
-                        .split('.')
-                        .map(|v| v.parse::<i32>().unwrap());
-                    current_level.width = it.next().unwrap();
-                    current_level.height = it.next().unwrap();
+                    let size = lines.value(line)?;
+                    let mut it = size.split('.');
+                    let width = lines.number(it.next().unwrap_or(""))?;
+                    let height = lines.number(it.next().unwrap_or(""))?;
+                    let sizes = 1..=MAX_LEVEL_SIZE;
+                    if !sizes.contains(&width) || !sizes.contains(&height) {
+                        return Err(lines.error(&format!("invalid level size: {}", size)));
+                    }
+                    current_level.width = width;
+                    current_level.height = height;
                 }
                 "[data]" => {
                     collecting_data = true;
                 }
+                "[notes]" => {
+                    collecting_notes = true;
+                }
                 "[additional]" => {
-                    let cnt = lines.next().unwrap().parse::<usize>().unwrap();
+                    let cnt = lines.value(line)?;
+                    let cnt: usize = lines.number(cnt)?;
                     for _ in 0..cnt {
-                        let line = lines.next().unwrap();
-                        let parts = line.split('.').collect::<Vec<&str>>();
-                        let x = parts[0].parse::<usize>().unwrap();
-                        let y = parts[1].parse::<usize>().unwrap();
-                        let c = parts[2].chars().next().unwrap();
-                        if c != current_level.tiles[y].chars().nth(x).unwrap() {
-                            log!(
-                                "level:{} additional data mismatch: {}.{}.{}",
-                                current_level.number,
-                                x,
-                                y,
-                                c
-                            );
-                        };
-                        let params = parts[3..]
-                            .iter()
-                            .map(|v| v.parse::<u16>().unwrap())
-                            .collect::<Vec<u16>>();
-                        current_level.additional.insert((x, y), params);
+                        let line = lines.value("[additional]")?;
+                        current_level.parse_additional(line, &lines)?;
                     }
                 }
                 "[end]" => {
                     if current_level.color.is_empty() {
                         current_level.color = default_level_color.clone();
                     }
+                    current_level.clamp_tiles();
                     levels.push(current_level);
                     current_level = Level::new();
                 }
                 _ => {
                     if collecting_data {
                         current_level.tiles.push(String::from(line));
+                    } else if collecting_notes {
+                        notes.push(line.trim_end());
                     }
                 }
             }
         }
 
-        LevelSet {
-            name: String::from(level_set_name.unwrap()),
-            levels,
+        let name = level_set_name.ok_or_else(|| lines.error("missing [name]"))?;
+        if levels.is_empty() {
+            return Err(lines.error("no levels"));
         }
+        Ok(LevelSet {
+            name: String::from(name),
+            notes: notes.join("\n").trim().to_string(),
+            rules,
+            levels,
+        })
     }
     pub fn size(&self) -> usize {
         self.levels.len()
@@ -118,4 +196,57 @@ impl Level {
             additional: AdditionalMap::new(),
         }
     }
+
+    /// Hash of the level geometry, data and additional parameters, so replays
+    /// recorded on another version of the level can be told apart
+    pub fn checksum(&self) -> u64 {
+        let mut additional = self.additional.iter().collect::<Vec<_>>();
+        additional.sort();
+        fnv1a(&format!(
+            "{}.{}\n{}\n{:?}",
+            self.width,
+            self.height,
+            self.tiles.join("\n"),
+            additional
+        ))
+    }
+
+    /// `x.y.symbol.params...` line of the `[additional]` section
+    fn parse_additional(&mut self, line: &str, lines: &Lines) -> Result<(), ParseError> {
+        let parts = line.split('.').collect::<Vec<&str>>();
+        if parts.len() < 3 {
+            return Err(lines.error(&format!("invalid additional data: {}", line)));
+        }
+        let x: usize = lines.number(parts[0])?;
+        let y: usize = lines.number(parts[1])?;
+        if x >= self.width as usize || y >= self.height as usize {
+            return Err(lines.error(&format!("additional data outside of the level: {}", line)));
+        }
+        let c = parts[2].chars().next();
+        if c != self.tiles.get(y).and_then(|row| row.chars().nth(x)) {
+            log!("level:{} additional data mismatch: {}", self.number, line);
+        };
+        let params = parts[3..]
+            .iter()
+            .map(|v| lines.number(v))
+            .collect::<Result<Vec<u16>, ParseError>>()?;
+        self.additional.insert((x, y), params);
+        Ok(())
+    }
+
+    /// Fits `[data]` rows to `[size]`, cutting off or filling up with empty cells
+    fn clamp_tiles(&mut self) {
+        let (width, height) = (self.width as usize, self.height as usize);
+        if self.tiles.len() != height || self.tiles.iter().any(|row| row.chars().count() != width) {
+            log!("level:{} data does not match size {}x{}", self.number, width, height);
+        }
+        self.tiles.resize(height, String::new());
+        for row in self.tiles.iter_mut() {
+            let mut cells: String = row.chars().take(width).collect();
+            while cells.chars().count() < width {
+                cells.push('.');
+            }
+            *row = cells;
+        }
+    }
 }
@@ -6,21 +6,68 @@ extern crate rand;
 
 #[macro_use]
 mod log;
+mod audio;
 mod board;
 mod consts;
+mod death;
+mod events;
+mod frames;
+mod hud;
 mod items;
+mod leaderboard;
 mod levels;
+mod library;
+mod moves;
+mod registry;
+mod replay;
+mod rng;
+mod rules;
+mod skin;
 mod sound;
+mod stats;
+mod storage;
 mod types;
 mod utils;
 mod tiles;
-use board::Board;
+use audio::{AudioEngine, SoundSet, Voice};
+use board::{Board, LevelState};
 use cfg_if::cfg_if;
+use death::{Death, DeathCause, DeathInfo};
+use events::{Event, GameEvent};
+use hud::Hud;
+use leaderboard::{Leaderboard, LeaderboardEntry};
 use levels::LevelSet;
+use library::{Library, PackInfo, Progress};
+use moves::Move;
 use log::log;
+use registry::Registry;
+use replay::{Input, Replay};
+use skin::Skin;
+use sound::Sound;
+use std::rc::Rc;
+use stats::Stats;
+use storage::Storage;
+use types::Position;
 use utils::{modulo, set_panic_hook};
 use wasm_bindgen::prelude::*;
 
+/// Engine internals, exported for the benchmarks and tests
+#[doc(hidden)]
+pub mod engine {
+    pub use board::{Board, LevelState, Restart};
+    pub use events::GameEvent;
+    pub use levels::LevelSet;
+    pub use library::Progress;
+    pub use registry::{ItemDef, Registry};
+    pub use replay::{Input, Replay};
+    pub use rules::RuleSet;
+    pub use skin::Skin;
+    pub use sound::Sound;
+    pub use storage::Storage;
+    pub use tiles::Tile;
+    pub use types::Kind;
+}
+
 cfg_if! {
     // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
     // allocator.
@@ -33,6 +80,7 @@ cfg_if! {
 
 #[wasm_bindgen]
 #[derive(Debug)]
+#[allow(dead_code)]
 pub struct ArrowKeys {
     right: i32,
     down: i32,
@@ -40,37 +88,133 @@ pub struct ArrowKeys {
     up: i32,
 }
 
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedMode {
+    /// every level and retry uses the seed passed to `set_seed`
+    Fixed,
+    /// seed derived from pack name and level number
+    PerLevel,
+    /// new random seed on every level load and retry
+    RandomEachRetry,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayMode {
+    /// any level can be played, levels may be skipped
+    Practice,
+    /// levels unlock one by one as they are completed
+    Campaign,
+}
+
+impl PlayMode {
+    fn key(pack: &str) -> String {
+        format!("play_mode_{}", pack)
+    }
+
+    fn load(storage: &dyn Storage, pack: &str) -> PlayMode {
+        match storage.get(&PlayMode::key(pack)).as_deref() {
+            Some("campaign") => PlayMode::Campaign,
+            _ => PlayMode::Practice,
+        }
+    }
+
+    fn save(self, storage: &mut dyn Storage, pack: &str) {
+        let value = match self {
+            PlayMode::Practice => "practice",
+            PlayMode::Campaign => "campaign",
+        };
+        storage.set(&PlayMode::key(pack), value);
+    }
+
+    /// `level` if it exists and may be played in this mode, the highest unlocked level otherwise
+    fn playable_level(self, progress: &Progress, level: usize, level_count: usize) -> usize {
+        let level = level.min(level_count - 1);
+        if self == PlayMode::Campaign && !progress.is_unlocked(level) {
+            progress.last_unlocked(level_count)
+        } else {
+            level
+        }
+    }
+}
+
 #[wasm_bindgen]
 pub struct Universe {
     current_level: usize,
-    level_set: LevelSet,
+    library: Library,
+    level_set: Rc<LevelSet>,
+    progress: Progress,
     board: Board,
+    replay: Replay,
+    storage: Box<dyn Storage>,
+    lives: usize,
+    score: usize,
+    events: Vec<GameEvent>,
+    last_death: Option<Death>,
+    seed: u64,
+    seed_mode: SeedMode,
+    play_mode: PlayMode,
+    pause_on_level_complete: bool,
+    level_complete_ticks: Option<usize>,
+    registry: Rc<Registry>,
+    audio: AudioEngine,
+    voices: Vec<Voice>,
+    rotated_view: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    mixer: audio::Mixer,
 }
 
 /// Public methods, exported to JavaScript.
 #[wasm_bindgen]
 impl Universe {
     pub fn reload_level(&mut self) {
-        let level = &self.level_set.levels[self.current_level];
-        log(&format!("{:#?}", level));
-        self.board = Board::from(level);
+        self.board.stats.retries += 1;
+        self.restart_level();
     }
 
+    /// Self-destruct (Esc), Robbo explodes as soon as it is on the board and the level restarts
     pub fn kill_robbo(&mut self) {
-        self.board.kill_robbo();
+        self.input(Input::Kill);
     }
 
     pub fn prev_level(&mut self) {
-        self.current_level = modulo(
+        let level = modulo(
             self.current_level as i32 - 1,
             self.level_set.size() as i32,
         ) as usize;
-        self.reload_level();
+        self.select_level(level);
     }
     pub fn next_level(&mut self) {
-        self.current_level =
-            (self.current_level + 1) % self.level_set.size();
-        self.reload_level();
+        let level = (self.current_level + 1) % self.level_set.size();
+        self.select_level(level);
+    }
+
+    /// Jumps to the level if the play mode allows it
+    pub fn select_level(&mut self, level: usize) -> bool {
+        if level >= self.level_set.size() || !self.is_level_unlocked(level) {
+            return false;
+        }
+        self.current_level = level;
+        self.load_level();
+        true
+    }
+
+    pub fn is_level_unlocked(&self, level: usize) -> bool {
+        self.play_mode == PlayMode::Practice || self.progress.is_unlocked(level)
+    }
+
+    pub fn set_play_mode(&mut self, play_mode: PlayMode) {
+        self.play_mode = play_mode;
+        play_mode.save(self.storage.as_mut(), &self.level_set.name);
+        if !self.is_level_unlocked(self.current_level) {
+            let level = self.progress.last_unlocked(self.level_set.size());
+            self.select_level(level);
+        }
+    }
+
+    pub fn get_play_mode(&self) -> PlayMode {
+        self.play_mode
     }
 
     pub fn toggle_god_mode(&mut self) {
@@ -81,68 +225,455 @@ impl Universe {
     }
 
     pub fn robbo_move(&mut self, kx: i32, ky: i32) {
-        self.board.robbo_move_or_shot((kx, ky), false)
+        self.input(Input::Move((kx, ky)))
     }
 
     pub fn robbo_shot(&mut self, kx: i32, ky: i32) {
-        self.board.robbo_move_or_shot((kx, ky), true)
+        self.input(Input::Shot((kx, ky)))
     }
 
-    pub fn new(level_data: String, current_level: usize) -> Universe {
-        set_panic_hook();
-        let level_set = LevelSet::parse(&level_data);
-        let board = Board::from(&level_set.levels[current_level]);
-
-        Universe {
-            current_level,
-            level_set,
-            board,
-        }
+    /// Fails with the parse error of an invalid pack
+    pub fn new(level_data: String, current_level: usize) -> Result<Universe, String> {
+        Universe::with_storage(level_data, current_level, storage::default_storage())
     }
 
     pub fn get_current_level(&self) -> usize {
         self.current_level
     }
 
-    pub fn get_inventory(&self) -> String {
+    /// Adds a level pack to the library, replacing a pack of the same name.
+    /// Returns the pack name, used to select it, or the parse error
+    pub fn add_pack(&mut self, level_data: String) -> Result<String, String> {
+        let level_set = LevelSet::parse(&level_data).map_err(|err| err.to_string())?;
+        let level_set = self.library.add(level_set);
+        if level_set.name == self.level_set.name {
+            self.level_set = level_set.clone();
+            let (level, level_count) = (self.current_level, level_set.size());
+            self.current_level = self.play_mode.playable_level(&self.progress, level, level_count);
+        }
+        Ok(level_set.name.clone())
+    }
+
+    pub fn get_packs(&self) -> Vec<PackInfo> {
+        self.library
+            .packs()
+            .iter()
+            .map(|level_set| {
+                if level_set.name == self.level_set.name {
+                    PackInfo::new(level_set, &self.get_progress())
+                } else {
+                    PackInfo::new(level_set, &Progress::load(self.storage.as_ref(), &level_set.name))
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_pack_name(&self) -> String {
+        self.level_set.name.clone()
+    }
+
+    /// Switches to another pack, restoring its saved progress and play mode
+    pub fn select_pack(&mut self, name: String) -> bool {
+        let level_set = match self.library.get(&name) {
+            Some(level_set) => level_set,
+            None => return false,
+        };
+        // the pack being played is saved on every level load; saving it here would overwrite
+        // its saved level with the one the Universe was constructed with
+        if level_set.name != self.level_set.name {
+            self.save_progress();
+        }
+        self.progress = Progress::load_and_migrate(self.storage.as_mut(), &level_set.name);
+        self.play_mode = PlayMode::load(self.storage.as_ref(), &level_set.name);
+        let level = self.progress.current_level;
+        self.current_level = self.play_mode.playable_level(&self.progress, level, level_set.size());
+        self.level_set = level_set;
+        self.load_level();
+        true
+    }
+
+    /// Levels completed in campaign mode
+    pub fn get_completed_levels(&self) -> Vec<usize> {
+        self.progress.completed.iter().cloned().collect()
+    }
+
+    pub fn get_hud(&self) -> Hud {
         let inventory = &self.board.robbo.inventory;
-        format!(
-            "level: {:02} screws: {:02} keys: {:02} bullets: {:02}",
-            self.current_level + 1,
-            self.board.missing_screws - inventory.screws,
-            inventory.keys,
-            inventory.bullets
-        )
+        Hud {
+            level: self.current_level + 1,
+            pack_name: self.level_set.name.clone(),
+            screws: self.board.remaining_screws(),
+            keys: inventory.keys,
+            bullets: inventory.bullets,
+            lives: self.lives,
+            score: self.get_score(),
+            capsule_repaired: self.board.is_capsule_repaired(),
+        }
+    }
+
+    /// Lives left, a new game starts when the last one is lost
+    pub fn get_lives(&self) -> usize {
+        self.lives
+    }
+
+    /// Score of the completed levels plus the items collected on the current one
+    pub fn get_score(&self) -> usize {
+        self.score + self.board.robbo.inventory.score
+    }
+
+    /// Preformatted HUD line, for debugging only - use `get_hud` for rendering
+    pub fn get_inventory(&self) -> String {
+        self.get_hud().to_string()
+    }
+
+    pub fn get_level_state(&self) -> LevelState {
+        self.board.get_level_state()
+    }
+
+    /// Keeps a completed level in `LevelState::LevelComplete` until `load_next_level`
+    /// is called, e.g. to show a summary screen; by default the next level loads at once
+    pub fn set_pause_on_level_complete(&mut self, pause: bool) {
+        self.pause_on_level_complete = pause;
+    }
+
+    /// Overrides how long the capsule takes off, in ticks, on this and every later board;
+    /// the pack's rule set decides otherwise
+    pub fn set_level_complete_ticks(&mut self, ticks: usize) {
+        self.level_complete_ticks = Some(ticks);
+        self.board.tiles.rules.level_complete_ticks = ticks;
     }
 
     pub fn load_next_level(&mut self) {
-        self.current_level += 1;
-        if self.current_level >= self.level_set.size() {
-            self.current_level = 0;
-        }
-        self.reload_level();
+        let level = (self.current_level + 1) % self.level_set.size();
+        self.select_level(level);
     }
 
     pub fn tick(&mut self) {
-        if self.board.finished {
-            self.load_next_level();
-            return;
-        }
-        self.board.tick();
-        if self.board.is_robbo_killed() {
-            self.reload_level();
-        }
+        self.step();
+        self.update_audio();
     }
     pub fn get_tile(&self, x: i32, y: i32) -> usize {
         self.board.get_tile((x, y))
     }
+    pub fn get_tile_at(&self, x: i32, y: i32, alpha: f64) -> usize {
+        self.board.get_tile_at((x, y), alpha)
+    }
     pub fn get_board_width(&self) -> i32 {
         self.board.width
     }
     pub fn get_board_height(&self) -> i32 {
         self.board.height
     }
-    pub fn get_sounds(&mut self) -> Vec<i16> {
-        self.board.get_sounds().iter().map(|v| *v as i16).collect()
+    /// Skin manifest, `[tiles]` may name any object known to the registry of the game
+    pub fn parse_skin(&self, data: &str) -> Skin {
+        Skin::parse(data, &self.registry)
+    }
+
+    pub fn set_sound_set(&mut self, sound_set: SoundSet) {
+        self.audio.set_sound_set(sound_set);
+    }
+
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.audio.set_master_volume(volume);
+    }
+
+    pub fn set_sound_volume(&mut self, sound: i16, volume: f32) {
+        if let Some(sound) = Sound::by_id(sound) {
+            self.audio.set_volume(sound, volume);
+        }
+    }
+
+    /// Board drawn rotated by 90 degrees, so panning follows the board's y axis
+    pub fn set_rotated_view(&mut self, rotated: bool) {
+        self.rotated_view = rotated;
+    }
+
+    /// Voices to start after the last tick
+    pub fn get_voices(&self) -> Vec<Voice> {
+        self.voices.clone()
+    }
+
+    pub fn get_sounds(&self) -> Vec<i16> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                GameEvent::Sound(sound, _) => Some(*sound as i16),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Objects moved during the last tick, for tweening sprites between cells
+    pub fn get_moves(&self) -> Vec<Move> {
+        self.board.get_moves().to_vec()
+    }
+
+    /// Events emitted during the last tick
+    pub fn get_events(&self) -> Vec<Event> {
+        self.events.iter().map(|event| Event::from(*event)).collect()
+    }
+
+    /// Cause of the most recent Robbo death, kept after the level is restarted
+    pub fn get_last_death(&self) -> Option<DeathInfo> {
+        self.last_death.map(DeathInfo::from)
+    }
+
+    /// Switches to `SeedMode::Fixed` with given seed and restarts the level
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.seed_mode = SeedMode::Fixed;
+        self.load_level();
+    }
+
+    pub fn set_seed_mode(&mut self, seed_mode: SeedMode) {
+        self.seed_mode = seed_mode;
+        self.load_level();
+    }
+
+    /// Seed of the current board
+    pub fn get_seed(&self) -> u64 {
+        self.board.seed
+    }
+
+    pub fn get_stats(&self) -> Stats {
+        self.board.stats
+    }
+
+    pub fn get_best_time(&self, level: usize) -> Option<LeaderboardEntry> {
+        self.get_leaderboard(level).best_time
+    }
+    pub fn get_fewest_moves(&self, level: usize) -> Option<LeaderboardEntry> {
+        self.get_leaderboard(level).fewest_moves
+    }
+    pub fn get_fewest_bullets(&self, level: usize) -> Option<LeaderboardEntry> {
+        self.get_leaderboard(level).fewest_bullets
+    }
+
+    /// Re-simulates stored replay on the level of its pack it was recorded on
+    /// and checks it reproduces every leaderboard entry linking to it. A replay
+    /// no entry links to is not verified
+    pub fn verify_replay(&self, replay_key: &str) -> bool {
+        let replay = match self.storage.get(replay_key).and_then(|data| Replay::parse(&data)) {
+            Some(replay) => replay,
+            None => return false,
+        };
+        let level_set = match self.library.get(&replay.pack) {
+            Some(level_set) => level_set,
+            None => return false,
+        };
+        let level = match level_set.levels.get(replay.level) {
+            Some(level) => level,
+            None => return false,
+        };
+        let record = match replay.play(level, level_set.rules, &self.registry) {
+            Some(record) => record,
+            None => return false,
+        };
+        let leaderboard = Leaderboard::load(self.storage.as_ref(), &replay.pack, replay.level);
+        let expected = [
+            (&leaderboard.best_time, record.ticks),
+            (&leaderboard.fewest_moves, record.moves),
+            (&leaderboard.fewest_bullets, record.bullets),
+        ];
+        let linked: Vec<_> = expected
+            .iter()
+            .filter_map(|(entry, value)| {
+                entry
+                    .as_ref()
+                    .filter(|e| e.replay_key() == replay_key)
+                    .map(|e| e.value == *value)
+            })
+            .collect();
+        !linked.is_empty() && linked.iter().all(|&matches| matches)
+    }
+}
+
+/// Rust-only setup, for embedders and tests
+impl Universe {
+    /// Like `new`, with progress, play modes and leaderboards kept in `storage`
+    pub fn with_storage(
+        level_data: String,
+        current_level: usize,
+        mut storage: Box<dyn Storage>,
+    ) -> Result<Universe, String> {
+        set_panic_hook();
+        let mut library = Library::new();
+        let level_set = library.add(LevelSet::parse(&level_data).map_err(|err| err.to_string())?);
+        let progress = Progress::load_and_migrate(storage.as_mut(), &level_set.name);
+        let play_mode = PlayMode::load(storage.as_ref(), &level_set.name);
+        let current_level = play_mode.playable_level(&progress, current_level, level_set.size());
+        let registry = Rc::new(Registry::default());
+        let board = Board::from(&level_set.levels[current_level], 0, level_set.rules, &registry);
+        let checksum = level_set.levels[current_level].checksum();
+        let replay = Replay::new(&level_set.name, current_level, checksum, 0);
+
+        Ok(Universe {
+            current_level,
+            library,
+            level_set,
+            progress,
+            board,
+            replay,
+            storage,
+            lives: consts::LIVES,
+            score: 0,
+            events: Vec::new(),
+            last_death: None,
+            seed: 0,
+            seed_mode: SeedMode::Fixed,
+            play_mode,
+            pause_on_level_complete: false,
+            level_complete_ticks: None,
+            registry,
+            audio: AudioEngine::new(SoundSet::default()),
+            voices: Vec::new(),
+            rotated_view: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            mixer: audio::Mixer::new(),
+        })
+    }
+
+    /// Replaces the symbols levels and skins are read with and reloads the level,
+    /// for forks adding objects of their own
+    pub fn set_registry(&mut self, registry: Registry) {
+        self.registry = Rc::new(registry);
+        self.load_level();
+    }
+}
+
+/// Audio output for native frontends, mixed in software
+#[cfg(not(target_arch = "wasm32"))]
+impl Universe {
+    /// Mono PCM samples of the sound, at the output sample rate
+    pub fn load_sound_clip(&mut self, sound: i16, samples: Vec<f32>) {
+        if let Some(sound) = Sound::by_id(sound) {
+            self.mixer.load(sound, samples);
+        }
+    }
+
+    /// Fills `out` with interleaved stereo samples of the playing sounds
+    pub fn mix_audio(&mut self, out: &mut [f32]) {
+        self.mixer.mix(out);
+    }
+}
+
+impl Universe {
+    fn step(&mut self) {
+        self.events = self.board.take_events();
+        if self.board.get_level_state() == LevelState::LevelComplete {
+            if !self.pause_on_level_complete {
+                self.load_next_level();
+            }
+            return;
+        }
+        self.board.tick();
+        self.events.extend(self.board.take_events());
+        if self.board.get_level_state() == LevelState::LevelComplete {
+            self.complete_level();
+        } else if self.board.is_robbo_killed() {
+            self.board.stats.deaths += 1;
+            self.last_death = self.board.get_death();
+            if let Some(death) = self.last_death {
+                log!("level {}: {}", self.current_level + 1, death);
+                if death.cause == DeathCause::SelfDestruct {
+                    self.board.stats.retries += 1;
+                }
+            }
+            self.lose_life();
+            self.restart_level();
+        }
+    }
+
+    fn complete_level(&mut self) {
+        self.score += self.board.robbo.inventory.score;
+        // levels reached by skipping in practice must not unlock the campaign
+        if self.play_mode == PlayMode::Campaign {
+            self.progress.completed.insert(self.current_level);
+        }
+        self.save_progress();
+        self.submit_run();
+    }
+
+    fn update_audio(&mut self) {
+        let (x_axis, width) = if self.rotated_view {
+            (false, self.board.height)
+        } else {
+            (true, self.board.width)
+        };
+        let pan = |(x, y): Position| {
+            let v = if x_axis { x } else { y };
+            (2 * v + 1) as f32 / width.max(1) as f32 - 1.0
+        };
+        self.voices = self.audio.update(&self.events, &pan);
+        #[cfg(not(target_arch = "wasm32"))]
+        for voice in self.voices.iter() {
+            self.mixer.play(voice);
+        }
+    }
+
+    /// Loads the level again, keeping deaths and retries
+    fn restart_level(&mut self) {
+        let stats = self.board.stats.next_attempt();
+        self.load_level();
+        self.board.stats = stats;
+    }
+
+    fn load_level(&mut self) {
+        let level = &self.level_set.levels[self.current_level];
+        log(&format!("{:#?}", level));
+        let seed = match self.seed_mode {
+            SeedMode::Fixed => self.seed,
+            SeedMode::PerLevel => rng::level_seed(&self.level_set.name, level.number),
+            SeedMode::RandomEachRetry => rng::random_seed(),
+        };
+        let mut rules = self.level_set.rules;
+        if let Some(ticks) = self.level_complete_ticks {
+            rules.level_complete_ticks = ticks;
+        }
+        self.board = Board::from(level, seed, rules, &self.registry);
+        self.replay = Replay::new(&self.level_set.name, self.current_level, level.checksum(), seed);
+        self.save_progress();
+    }
+
+    fn get_progress(&self) -> Progress {
+        Progress {
+            current_level: self.current_level,
+            ..self.progress.clone()
+        }
+    }
+
+    fn save_progress(&mut self) {
+        self.progress = self.get_progress();
+        self.progress.save(self.storage.as_mut(), &self.level_set.name);
+    }
+
+    fn lose_life(&mut self) {
+        if self.lives > 1 {
+            self.lives -= 1;
+        } else {
+            log!("game over, score: {}", self.score);
+            self.lives = consts::LIVES;
+            self.score = 0;
+        }
+    }
+
+    fn input(&mut self, input: Input) {
+        self.replay.record(self.board.tiles.frame_cnt, input);
+        input.apply(&mut self.board);
+    }
+
+    fn get_leaderboard(&self, level: usize) -> Leaderboard {
+        Leaderboard::load(self.storage.as_ref(), &self.level_set.name, level)
+    }
+
+    fn submit_run(&mut self) {
+        let record = self.board.get_run_record();
+        let pack = &self.level_set.name;
+        let replay_key = Leaderboard::replay_key(pack, self.current_level, &record);
+        let mut leaderboard = self.get_leaderboard(self.current_level);
+        if leaderboard.submit(&record, &replay_key) {
+            self.storage.set(&replay_key, &self.replay.serialize());
+            leaderboard.save(self.storage.as_mut(), pack, self.current_level);
+        }
     }
 }
@@ -0,0 +1,154 @@
+use levels::LevelSet;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use storage::Storage;
+use wasm_bindgen::prelude::*;
+
+/// Level packs available in the game, identified by their `[name]`
+pub struct Library {
+    packs: Vec<Rc<LevelSet>>,
+}
+
+impl Library {
+    pub fn new() -> Library {
+        Library { packs: Vec::new() }
+    }
+
+    /// Adds a pack, replacing an already added pack of the same name
+    pub fn add(&mut self, level_set: LevelSet) -> Rc<LevelSet> {
+        let level_set = Rc::new(level_set);
+        match self.packs.iter().position(|p| p.name == level_set.name) {
+            Some(index) => self.packs[index] = level_set.clone(),
+            None => self.packs.push(level_set.clone()),
+        }
+        level_set
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rc<LevelSet>> {
+        self.packs.iter().find(|p| p.name == name).cloned()
+    }
+
+    pub fn packs(&self) -> &[Rc<LevelSet>] {
+        &self.packs
+    }
+}
+
+/// Player's progress in a single pack
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub current_level: usize,
+    pub completed: BTreeSet<usize>,
+}
+
+impl Progress {
+    fn key(pack: &str) -> String {
+        format!("progress_{}", pack)
+    }
+
+    /// First level is always open, every other one opens once the previous level is completed
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level == 0 || self.completed.contains(&(level - 1)) || self.completed.contains(&level)
+    }
+
+    /// Highest level open for play, never above `level_count - 1`
+    pub fn last_unlocked(&self, level_count: usize) -> usize {
+        (0..level_count).rev().find(|level| self.is_unlocked(*level)).unwrap_or(0)
+    }
+
+    /// Key the web frontend saved only the current level under before progress was
+    /// kept per pack, `<id>` being the name of the level file. For the bundled packs
+    /// that is the pack name in lower case
+    fn legacy_key(pack: &str) -> String {
+        format!("current_level_{}", pack.to_lowercase())
+    }
+
+    fn load_legacy(storage: &dyn Storage, pack: &str) -> Option<Progress> {
+        let current_level = storage.get(&Progress::legacy_key(pack))?.trim().parse().ok()?;
+        Some(Progress {
+            current_level,
+            ..Progress::default()
+        })
+    }
+
+    /// Saved progress of the pack, falling back to the current level saved under the legacy key
+    pub fn load(storage: &dyn Storage, pack: &str) -> Progress {
+        match storage.get(&Progress::key(pack)) {
+            Some(data) => Progress::parse(&data),
+            None => Progress::load_legacy(storage, pack).unwrap_or_default(),
+        }
+    }
+
+    /// Like `load`, and progress found only under the legacy key is saved under the new one
+    pub fn load_and_migrate(storage: &mut dyn Storage, pack: &str) -> Progress {
+        let progress = Progress::load(storage, pack);
+        let saved = storage.get(&Progress::key(pack)).is_some();
+        if !saved && storage.get(&Progress::legacy_key(pack)).is_some() {
+            progress.save(storage, pack);
+        }
+        progress
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, pack: &str) {
+        storage.set(&Progress::key(pack), &self.serialize());
+    }
+
+    fn serialize(&self) -> String {
+        let completed = self
+            .completed
+            .iter()
+            .map(|level| format!(".{}", level))
+            .collect::<String>();
+        format!("current.{}\ncompleted{}", self.current_level, completed)
+    }
+
+    fn parse(data: &str) -> Progress {
+        let mut progress = Progress::default();
+        for line in data.lines() {
+            let mut parts = line.split('.');
+            let name = parts.next();
+            let mut values = parts.filter_map(|v| v.parse::<usize>().ok());
+            match name {
+                Some("current") => progress.current_level = values.next().unwrap_or(0),
+                Some("completed") => progress.completed = values.collect(),
+                _ => (),
+            }
+        }
+        progress
+    }
+}
+
+/// Pack description for the pack browser
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackInfo {
+    name: String,
+    notes: String,
+    pub level_count: usize,
+    pub current_level: usize,
+    pub completed_count: usize,
+}
+
+#[wasm_bindgen]
+impl PackInfo {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn notes(&self) -> String {
+        self.notes.clone()
+    }
+}
+
+impl PackInfo {
+    pub fn new(level_set: &LevelSet, progress: &Progress) -> PackInfo {
+        PackInfo {
+            name: level_set.name.clone(),
+            notes: level_set.notes.clone(),
+            level_count: level_set.size(),
+            current_level: progress.current_level,
+            completed_count: progress.completed.len(),
+        }
+    }
+}
@@ -1,11 +1,17 @@
 extern crate web_sys;
-use wasm_bindgen::prelude::*;
-use web_sys::console;
 
+#[cfg(target_arch = "wasm32")]
 pub fn log(text: &str) {
+    use wasm_bindgen::prelude::*;
+    use web_sys::console;
     console::log_1(&JsValue::from_str(text));
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+pub fn log(text: &str) {
+    eprintln!("{}", text);
+}
+
 macro_rules! log {
     ( $( $x:expr ),* ) => {log(&format!($( $x, )*))}
 }
@@ -0,0 +1,71 @@
+use types::{Kind, Position};
+use wasm_bindgen::prelude::*;
+
+/// Single object displacement during the last tick, used by renderers to tween
+/// sprites between cells. `interpolable` is false for jumps (teleports, wrap-arounds)
+/// which should be drawn at the destination right away.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub kind: i32,
+    pub from_x: i32,
+    pub from_y: i32,
+    pub to_x: i32,
+    pub to_y: i32,
+    pub interpolable: bool,
+}
+
+impl Move {
+    pub fn get_from(&self) -> Position {
+        (self.from_x, self.from_y)
+    }
+    pub fn get_to(&self) -> Position {
+        (self.to_x, self.to_y)
+    }
+}
+
+pub struct Moves {
+    moves: Vec<Move>,
+}
+
+impl Moves {
+    pub fn new() -> Moves {
+        Moves { moves: Vec::new() }
+    }
+    pub fn clear(&mut self) {
+        self.moves.clear()
+    }
+    /// Records a displacement; consecutive moves of the same object within a tick
+    /// are merged, and the result is only interpolable if it spans a single cell.
+    pub fn record(&mut self, kind: Kind, from: Position, to: Position, interpolable: bool) {
+        if from == to {
+            return;
+        }
+        let previous = self
+            .moves
+            .iter()
+            .position(|m| m.kind == kind as i32 && m.get_to() == from);
+        let (from, interpolable) = match previous {
+            Some(index) => {
+                let m = self.moves.remove(index);
+                (m.get_from(), m.interpolable && interpolable)
+            }
+            None => (from, interpolable),
+        };
+        if from == to {
+            return;
+        }
+        let is_adjacent = (from.0 - to.0).abs() + (from.1 - to.1).abs() == 1;
+        self.moves.push(Move {
+            kind: kind as i32,
+            from_x: from.0,
+            from_y: from.1,
+            to_x: to.0,
+            to_y: to.1,
+            interpolable: interpolable && is_adjacent,
+        });
+    }
+    pub fn get(&self) -> &[Move] {
+        &self.moves
+    }
+}
@@ -0,0 +1,258 @@
+use consts;
+use items::{
+    Animation, BlastHead, Bear, Bird, Bomb, Bullet, Butterfly, Capsule, Door, ForceField, Gun,
+    ItemKind, Magnet, PushBox, Robbo, SimpleItem, Teleport,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+use tiles::Tile;
+use types::{Flags, Kind};
+
+pub type Constructor = fn(SimpleItem, &[u16]) -> ItemKind;
+
+/// Definition of a dynamic object placed on the board from a level symbol,
+/// items created from it share its tiles
+#[derive(Clone)]
+pub struct ItemDef {
+    pub kind: Kind,
+    pub tiles: Rc<[usize]>,
+    pub flags: Flags,
+    pub default_params: Vec<u16>,
+    pub constructor: Constructor,
+}
+
+impl ItemDef {
+    pub fn new(kind: Kind, tiles: Vec<usize>, constructor: Constructor) -> ItemDef {
+        ItemDef {
+            kind,
+            tiles: tiles.into(),
+            flags: 0,
+            default_params: Vec::new(),
+            constructor,
+        }
+    }
+    pub fn flags(self, flags: Flags) -> ItemDef {
+        ItemDef { flags, ..self }
+    }
+    pub fn default_params(self, default_params: Vec<u16>) -> ItemDef {
+        ItemDef {
+            default_params,
+            ..self
+        }
+    }
+    /// Creates the item, params missing in `params` are taken from `default_params`
+    pub fn create(&self, params: Option<&[u16]>) -> ItemKind {
+        let simple_item = SimpleItem::new(self.kind, self.tiles.clone()).flags(self.flags);
+        let mut all_params = params.unwrap_or(&[]).to_vec();
+        if all_params.len() < self.default_params.len() {
+            all_params.extend_from_slice(&self.default_params[all_params.len()..]);
+        }
+        (self.constructor)(simple_item, &all_params)
+    }
+}
+
+#[derive(Clone)]
+pub enum ObjectDef {
+    /// static cell without behavior: walls, ground and collectables
+    Tile(Tile),
+    Item(ItemDef),
+}
+
+/// Maps level symbols to objects, `Registry::default()` knows every GNU Robbo symbol.
+/// Objects are also named, skins map the logical tiles of an object by its name
+#[derive(Clone)]
+pub struct Registry {
+    objects: HashMap<char, ObjectDef>,
+    names: HashMap<String, Vec<usize>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            objects: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+    pub fn register_tile(&mut self, symbol: char, name: &str, tile: Tile) {
+        self.register_sprite(name, &[tile.get_tile()]);
+        self.objects.insert(symbol, ObjectDef::Tile(tile));
+    }
+    pub fn register(&mut self, symbol: char, name: &str, def: ItemDef) {
+        self.register_sprite(name, &def.tiles);
+        self.objects.insert(symbol, ObjectDef::Item(def));
+    }
+    /// Names logical tiles of an object without a level symbol, e.g. bullets.
+    /// Tiles registered under the same name are merged
+    pub fn register_sprite(&mut self, name: &str, tiles: &[usize]) {
+        let named = self.names.entry(String::from(name)).or_default();
+        for tile in tiles {
+            if !named.contains(tile) {
+                named.push(*tile);
+            }
+        }
+    }
+    pub fn get(&self, symbol: char) -> Option<&ObjectDef> {
+        self.objects.get(&symbol)
+    }
+    /// Logical tiles of the named object, in registration order
+    pub fn logical_tiles(&self, name: &str) -> Option<&[usize]> {
+        self.names.get(name).map(|tiles| tiles.as_slice())
+    }
+    /// Level symbol of the object showing `tile`, used to print boards.
+    /// Items are matched by kind, preferring the symbol whose frames contain the tile
+    pub fn symbol(&self, tile: &Tile) -> Option<char> {
+        self.objects
+            .iter()
+            .filter_map(|(symbol, object)| match object {
+                ObjectDef::Tile(t) if t == tile => Some((0, *symbol)),
+                ObjectDef::Item(def) if def.kind == tile.get_kind() => {
+                    let rank = if def.tiles.contains(&tile.get_tile()) { 1 } else { 2 };
+                    Some((rank, *symbol))
+                }
+                _ => None,
+            })
+            .min()
+            .map(|(_, symbol)| symbol)
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        let mut registry = Registry::new();
+        registry.register_tile('O', "wall", Tile::wall(2));
+        registry.register_tile('o', "green_wall", Tile::wall(29));
+        registry.register_tile('-', "black_wall", Tile::wall(19));
+        registry.register_tile('Q', "red_wall", Tile::wall(3));
+        registry.register_tile('q', "fat_wall", Tile::wall(21));
+        registry.register_tile('p', "round_wall", Tile::wall(68));
+        registry.register_tile('P', "boulder_wall", Tile::wall(69));
+        registry.register_tile('s', "square_wall", Tile::wall(10));
+        registry.register_tile('S', "lattice_wall", Tile::wall(22));
+        registry.register_tile('H', "ground", Tile::ground());
+        registry.register_tile('T', "screw", Tile::screw());
+        registry.register_tile('\'', "ammo", Tile::ammo());
+        registry.register_tile('%', "key", Tile::key());
+
+        registry.register(
+            'R',
+            "robbo",
+            ItemDef::new(Kind::Robbo, Robbo::TILES.to_vec(), |_, _| Animation::spawn_robbo().into()),
+        );
+        registry.register(
+            'D',
+            "door",
+            ItemDef::new(Kind::Door, vec![9], |item, _| Door::new(item).into()),
+        );
+        registry.register(
+            '#',
+            "box",
+            ItemDef::new(Kind::ABox, vec![20], |item, _| item.into()).flags(consts::MOVEABLE),
+        );
+        registry.register(
+            '&',
+            "teleport",
+            ItemDef::new(Kind::Teleport, vec![48, 49], |item, params| {
+                Teleport::new(item, params).into()
+            })
+            .default_params(vec![0, 0]),
+        );
+        registry.register(
+            '!',
+            "capsule",
+            ItemDef::new(Kind::Capsule, vec![17, 18], |item, _| Capsule::new(item).into())
+                .flags(consts::MOVEABLE | consts::UNDESTROYABLE),
+        );
+        registry.register(
+            '~',
+            "push_box",
+            ItemDef::new(Kind::ABox, vec![6], |item, _| PushBox::new(item).into())
+                .flags(consts::MOVEABLE),
+        );
+        registry.register(
+            'b',
+            "bomb",
+            ItemDef::new(Kind::Bomb, vec![8], |item, _| Bomb::new(item).into())
+                .flags(consts::DESTROYABLE | consts::MOVEABLE),
+        );
+        registry.register(
+            '?',
+            "questionmark",
+            ItemDef::new(Kind::Questionmark, vec![12], |item, _| item.into())
+                .flags(consts::DESTROYABLE | consts::MOVEABLE),
+        );
+        registry.register(
+            'V',
+            "butterfly",
+            ItemDef::new(Kind::Butterfly, vec![32, 33], |item, _| Butterfly::new(item).into())
+                .flags(consts::DESTROYABLE | consts::DEADLY),
+        );
+        registry.register(
+            '@',
+            "bear",
+            ItemDef::new(Kind::Bear, vec![13, 14], |item, params| {
+                Bear::new(item, params).into()
+            })
+            .flags(consts::DESTROYABLE | consts::DEADLY)
+            .default_params(vec![0]),
+        );
+        registry.register(
+            '*',
+            "black_bear",
+            ItemDef::new(Kind::BlackBear, vec![30, 31], |item, params| {
+                Bear::new(item, params).into()
+            })
+            .flags(consts::DESTROYABLE | consts::DEADLY)
+            .default_params(vec![0]),
+        );
+        registry.register(
+            '^',
+            "bird",
+            ItemDef::new(Kind::Bird, vec![15, 16], |item, params| {
+                Bird::new(item, params).into()
+            })
+            .flags(consts::DESTROYABLE | consts::DEADLY)
+            .default_params(vec![0, 0, 0]),
+        );
+        registry.register(
+            '}',
+            "gun",
+            ItemDef::new(Kind::Gun, vec![53, 54, 55, 56], |item, params| {
+                Gun::new(item, params).into()
+            })
+            .default_params(vec![0, 0, 0, 0, 0, 0]),
+        );
+        registry.register(
+            'L',
+            "gun",
+            ItemDef::new(Kind::HorizontalLaser, vec![53], |item, _| item.into()),
+        );
+        registry.register(
+            'l',
+            "gun",
+            ItemDef::new(Kind::VerticalLaser, vec![53], |item, _| item.into()),
+        );
+        registry.register(
+            'M',
+            "magnet",
+            ItemDef::new(Kind::Magnet, vec![0, 72, 1, 73], |item, params| {
+                Magnet::new(item, params).into()
+            })
+            .default_params(vec![0]),
+        );
+        registry.register(
+            '=',
+            "force_field",
+            ItemDef::new(Kind::ForceField, vec![45, 57], |item, params| {
+                ForceField::new(item, params).into()
+            })
+            .flags(consts::DESTROYABLE)
+            .default_params(vec![0]),
+        );
+        registry.register_sprite("empty", &[Tile::empty().get_tile()]);
+        registry.register_sprite("bullet", Bullet::TILES);
+        registry.register_sprite("explosion", Animation::EXPLOSION);
+        registry.register_sprite("blast", BlastHead::TILES);
+        registry.register_sprite("blast", Animation::BLAST_TAIL);
+        registry
+    }
+}
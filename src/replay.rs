@@ -0,0 +1,137 @@
+use board::{Board, LevelState};
+use leaderboard::RunRecord;
+use levels::Level;
+use registry::Registry;
+use rules::RuleSet;
+use std::rc::Rc;
+use types::Direction;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Input {
+    Move(Direction),
+    Shot(Direction),
+    Kill,
+}
+
+impl Input {
+    pub fn apply(self, board: &mut Board) {
+        match self {
+            Input::Move(dir) => board.robbo_move_or_shot(dir, false),
+            Input::Shot(dir) => board.robbo_move_or_shot(dir, true),
+            Input::Kill => board.request_restart(),
+        }
+    }
+}
+
+/// Player inputs of a single level run, keyed by the board tick they were received at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Replay {
+    pub pack: String,
+    pub level: usize,
+    /// `Level::checksum` of the level the run was recorded on
+    pub checksum: u64,
+    pub seed: u64,
+    pub inputs: Vec<(usize, Input)>,
+}
+
+impl Replay {
+    const EXTRA_TICKS: usize = 1000;
+
+    pub fn new(pack: &str, level: usize, checksum: u64, seed: u64) -> Replay {
+        Replay {
+            pack: String::from(pack),
+            level,
+            checksum,
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, tick: usize, input: Input) {
+        self.inputs.push((tick, input));
+    }
+
+    /// Re-simulates the run from scratch, returns its record if it finishes the level.
+    /// Fails on a level other than the one the run was recorded on
+    pub fn play(&self, level: &Level, rules: RuleSet, registry: &Rc<Registry>) -> Option<RunRecord> {
+        if level.checksum() != self.checksum {
+            return None;
+        }
+        let mut board = Board::from(level, self.seed, rules, registry);
+        let max_ticks = self.inputs.last().map(|(tick, _)| *tick).unwrap_or(0) + Replay::EXTRA_TICKS;
+        let mut inputs = self.inputs.iter().peekable();
+        while board.tiles.frame_cnt <= max_ticks {
+            while let Some((_, input)) = inputs.next_if(|(tick, _)| *tick <= board.tiles.frame_cnt) {
+                input.apply(&mut board);
+            }
+            board.tick();
+            if board.get_level_state() != LevelState::Playing {
+                return Some(board.get_run_record());
+            }
+            if board.is_robbo_killed() {
+                return None;
+            }
+        }
+        None
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut lines = vec![
+            String::from("[pack]"),
+            self.pack.clone(),
+            String::from("[level]"),
+            self.level.to_string(),
+            String::from("[checksum]"),
+            self.checksum.to_string(),
+            String::from("[seed]"),
+            self.seed.to_string(),
+            String::from("[inputs]"),
+        ];
+        for (tick, input) in &self.inputs {
+            lines.push(match input {
+                Input::Move((dx, dy)) => format!("{}.m.{}.{}", tick, dx, dy),
+                Input::Shot((dx, dy)) => format!("{}.s.{}.{}", tick, dx, dy),
+                Input::Kill => format!("{}.k", tick),
+            });
+        }
+        lines.join("\n")
+    }
+
+    pub fn parse(data: &str) -> Option<Replay> {
+        let mut lines = data.lines();
+        if lines.next()? != "[pack]" {
+            return None;
+        }
+        let pack = lines.next()?;
+        if lines.next()? != "[level]" {
+            return None;
+        }
+        let level = lines.next()?.parse().ok()?;
+        if lines.next()? != "[checksum]" {
+            return None;
+        }
+        let checksum = lines.next()?.parse().ok()?;
+        if lines.next()? != "[seed]" {
+            return None;
+        }
+        let mut replay = Replay::new(pack, level, checksum, lines.next()?.parse().ok()?);
+        if lines.next()? != "[inputs]" {
+            return None;
+        }
+        for line in lines {
+            let parts = line.split('.').collect::<Vec<&str>>();
+            let tick = parts[0].parse::<usize>().ok()?;
+            let dir = || -> Option<Direction> {
+                Some((parts.get(2)?.parse().ok()?, parts.get(3)?.parse().ok()?))
+            };
+            let input = match *parts.get(1)? {
+                "m" => Input::Move(dir()?),
+                "s" => Input::Shot(dir()?),
+                "k" => Input::Kill,
+                _ => return None,
+            };
+            replay.record(tick, input);
+        }
+        Some(replay)
+    }
+}
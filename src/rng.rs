@@ -0,0 +1,88 @@
+use rand::{Error, RngCore};
+use std::fmt;
+use std::str::FromStr;
+use utils::fnv1a;
+
+/// PCG32 (XSH RR) generator, its whole state is a single `u64`
+/// so it can be cloned, stored in replays and restored later
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    const MULTIPLIER: u64 = 6_364_136_223_846_793_005;
+    const INCREMENT: u64 = 1_442_695_040_888_963_407;
+
+    pub fn new(seed: u64) -> GameRng {
+        let mut rng = GameRng { state: 0 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(GameRng::MULTIPLIER)
+            .wrapping_add(GameRng::INCREMENT);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        xorshifted.rotate_right((state >> 59) as u32)
+    }
+    fn next_u64(&mut self) -> u64 {
+        let low = u64::from(self.next_u32());
+        let high = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl fmt::Display for GameRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.state)
+    }
+}
+
+impl FromStr for GameRng {
+    type Err = std::num::ParseIntError;
+    fn from_str(s: &str) -> Result<GameRng, Self::Err> {
+        Ok(GameRng { state: s.parse()? })
+    }
+}
+
+/// Seed derived from pack name and level number, stable across runs and platforms
+pub fn level_seed(pack: &str, level: usize) -> u64 {
+    fnv1a(&format!("{}/{}", pack, level))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn random_seed() -> u64 {
+    let high = (js_sys::Math::random() * f64::from(u32::MAX)) as u64;
+    let low = (js_sys::Math::random() * f64::from(u32::MAX)) as u64;
+    (high << 32) | low
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn random_seed() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() ^ (u64::from(d.subsec_nanos()) << 32))
+        .unwrap_or(0)
+}
@@ -0,0 +1,63 @@
+/// Constants and quirks that differ between Robbo implementations.
+/// Probabilities are checked once per tick of the given item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RuleSet {
+    pub name: &'static str,
+    pub gun_shooting_probability: f64,
+    pub gun_rotate_probability: f64,
+    pub bird_shooting_probability: f64,
+    pub butterfly_move_probability: f64,
+    pub butterfly_random_move_probability: f64,
+    /// teleport searches counter-clockwise for a free cell when entered horizontally,
+    /// a special case of GNU Robbo needed by level 16 of the original pack
+    pub teleport_horizontal_counter_clockwise: bool,
+    /// ticks Robbo may stay hidden before the whole board explodes
+    pub hidden_robbo_explode_ticks: usize,
+    /// ticks Robbo stays hidden before the level is restarted
+    pub hidden_robbo_restart_ticks: usize,
+    /// ticks from Robbo entering the capsule until the level is complete
+    pub level_complete_ticks: usize,
+}
+
+impl RuleSet {
+    pub fn gnu_robbo() -> RuleSet {
+        RuleSet {
+            name: "gnu_robbo",
+            gun_shooting_probability: 0.075,
+            gun_rotate_probability: 0.25,
+            bird_shooting_probability: 0.1,
+            butterfly_move_probability: 1.0,
+            butterfly_random_move_probability: 0.1,
+            teleport_horizontal_counter_clockwise: true,
+            hidden_robbo_explode_ticks: 8,
+            hidden_robbo_restart_ticks: 20,
+            level_complete_ticks: 16,
+        }
+    }
+
+    /// The original Atari 8-bit game, as far as it differs from GNU Robbo in a documented way:
+    /// the counter-clockwise teleport search is a GNU Robbo hack for level 16 of the original
+    /// pack, the Atari teleport always searches clockwise. Timings and probabilities are not
+    /// documented for the Atari version, so they are the GNU Robbo ones.
+    pub fn atari() -> RuleSet {
+        RuleSet {
+            name: "atari",
+            teleport_horizontal_counter_clockwise: false,
+            ..RuleSet::gnu_robbo()
+        }
+    }
+
+    pub fn by_name(name: &str) -> Option<RuleSet> {
+        match name {
+            "gnu_robbo" => Some(RuleSet::gnu_robbo()),
+            "atari" => Some(RuleSet::atari()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> RuleSet {
+        RuleSet::gnu_robbo()
+    }
+}
@@ -0,0 +1,138 @@
+use log::log;
+use registry::Registry;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Skin manifest: atlas geometry, logical tile to atlas index mapping
+/// and the tile remap used when the board is drawn rotated
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Skin {
+    name: String,
+    atlas: String,
+    background: String,
+    pub tile_size: u32,
+    pub spacing: u32,
+    pub border: u32,
+    pub columns: u32,
+    tiles: HashMap<usize, usize>,
+    rotated: HashMap<usize, usize>,
+}
+
+#[wasm_bindgen]
+impl Skin {
+    #[wasm_bindgen(getter)]
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn atlas(&self) -> String {
+        self.atlas.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn background(&self) -> String {
+        self.background.clone()
+    }
+
+    /// Atlas index of a logical tile, optionally for the rotated view
+    pub fn resolve(&self, tile: usize, rotated: bool) -> usize {
+        let tile = if rotated {
+            *self.rotated.get(&tile).unwrap_or(&tile)
+        } else {
+            tile
+        };
+        *self.tiles.get(&tile).unwrap_or(&tile)
+    }
+
+    /// Left edge of atlas tile in pixels
+    pub fn atlas_x(&self, index: usize) -> u32 {
+        (index as u32 % self.columns) * self.spacing + self.border
+    }
+
+    /// Top edge of atlas tile in pixels
+    pub fn atlas_y(&self, index: usize) -> u32 {
+        (index as u32 / self.columns) * self.spacing + self.border
+    }
+}
+
+impl Skin {
+    pub fn new() -> Skin {
+        Skin {
+            name: String::from("original"),
+            atlas: String::from("icons32.png"),
+            background: String::from("608050"),
+            tile_size: 32,
+            spacing: 34,
+            border: 2,
+            columns: 12,
+            tiles: HashMap::new(),
+            rotated: HashMap::new(),
+        }
+    }
+
+    /// Object names in `[tiles]` are the names known to `registry`
+    pub fn parse(data: &str, registry: &Registry) -> Skin {
+        let mut skin = Skin::new();
+        let mut section = "";
+        for line in data.lines().map(|line| line.trim()).filter(|line| !line.is_empty()) {
+            if line.starts_with('[') {
+                section = line;
+                continue;
+            }
+            match section {
+                "[name]" => skin.name = String::from(line),
+                "[atlas]" => skin.atlas = String::from(line),
+                "[background]" => skin.background = String::from(line),
+                "[tile_size]" => skin.tile_size = line.parse().unwrap_or(skin.tile_size),
+                "[spacing]" => skin.spacing = line.parse().unwrap_or(skin.spacing),
+                "[border]" => skin.border = line.parse().unwrap_or(skin.border),
+                // zero columns would divide by zero in `atlas_x` and `atlas_y`
+                "[columns]" => {
+                    skin.columns = line.parse().ok().filter(|c| *c > 0).unwrap_or(skin.columns)
+                }
+                "[tiles]" => skin.parse_tiles(line, registry),
+                "[rotated]" => skin.parse_rotated(line),
+                _ => log!("skin {}: unexpected line: {}", skin.name, line),
+            }
+        }
+        skin
+    }
+
+    /// `name.frame.frame...` - atlas frames for the logical tiles of given object
+    fn parse_tiles(&mut self, line: &str, registry: &Registry) {
+        let mut parts = line.split('.');
+        let name = parts.next().unwrap_or("");
+        let logical = registry.logical_tiles(name);
+        let frames = parts.map(|v| v.parse::<usize>()).collect::<Result<Vec<usize>, _>>();
+        match (logical, frames) {
+            (Some(logical), Ok(ref frames)) if !frames.is_empty() => {
+                for (i, tile) in logical.iter().enumerate() {
+                    self.tiles.insert(*tile, frames[i % frames.len()]);
+                }
+            }
+            _ => log!("skin {}: invalid tiles: {}", self.name, line),
+        }
+    }
+
+    /// `from.to` - logical tile drawn instead of `from` in the rotated view
+    fn parse_rotated(&mut self, line: &str) {
+        let pair = line
+            .split('.')
+            .map(|v| v.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>();
+        match pair {
+            Ok(ref pair) if pair.len() == 2 => {
+                self.rotated.insert(pair[0], pair[1]);
+            }
+            _ => log!("skin {}: invalid rotated entry: {}", self.name, line),
+        }
+    }
+}
+
+impl Default for Skin {
+    fn default() -> Skin {
+        Skin::new()
+    }
+}
@@ -1,6 +1,4 @@
-use std::cell::RefCell;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(i16)]
 pub enum Sound {
     Walk = 1,
@@ -15,24 +13,46 @@ pub enum Sound {
     Teleport = 10,
     Capsule = 11,
     Burn = 12,
+    Push = 13,
+    Bird = 14,
+    Magnet = 15,
+    ForceField = 16,
+    Laser = 17,
+    Kill = 18,
+    LevelEnd = 19,
 }
 
-pub struct Sounds {
-    sounds: RefCell<Vec<Sound>>
-}
+const SOUND_NAMES: &[(Sound, &str)] = &[
+    (Sound::Walk, "walk"),
+    (Sound::Spawn, "spawn"),
+    (Sound::Ammo, "ammo"),
+    (Sound::Key, "key"),
+    (Sound::Screw, "screw"),
+    (Sound::Bomb, "bomb"),
+    (Sound::Door, "door"),
+    (Sound::Shot, "shot"),
+    (Sound::GunShot, "gun_shot"),
+    (Sound::Teleport, "teleport"),
+    (Sound::Capsule, "capsule"),
+    (Sound::Burn, "burn"),
+    (Sound::Push, "push"),
+    (Sound::Bird, "bird"),
+    (Sound::Magnet, "magnet"),
+    (Sound::ForceField, "force_field"),
+    (Sound::Laser, "laser"),
+    (Sound::Kill, "kill"),
+    (Sound::LevelEnd, "level_end"),
+];
 
-impl Sounds {
-    pub fn new() -> Sounds {
-        Sounds {
-            sounds: RefCell::new(Vec::new())
-        }
+impl Sound {
+    /// Name used for the sound in sound set manifests
+    pub fn get_name(self) -> &'static str {
+        SOUND_NAMES.iter().find(|(s, _)| *s == self).map(|(_, name)| *name).unwrap()
     }
-    pub fn play_sound(&self, sound: Sound) {
-        let mut sounds = self.sounds.borrow_mut();
-        sounds.push(sound)
+    pub fn by_name(name: &str) -> Option<Sound> {
+        SOUND_NAMES.iter().find(|(_, n)| *n == name).map(|(sound, _)| *sound)
     }
-    pub fn get_sounds(&self) -> Vec<Sound> {
-        let mut sounds = self.sounds.borrow_mut();
-        sounds.drain(..).collect()
+    pub fn by_id(id: i16) -> Option<Sound> {
+        SOUND_NAMES.iter().find(|(s, _)| *s as i16 == id).map(|(sound, _)| *sound)
     }
-}
\ No newline at end of file
+}
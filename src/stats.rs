@@ -0,0 +1,34 @@
+use wasm_bindgen::prelude::*;
+
+/// Gameplay statistics of the current level
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// ticks since Robbo appeared on the board, the spawn animation is not counted
+    pub ticks: usize,
+    pub steps: usize,
+    /// steps Robbo was pulled by a magnet, included in `steps`
+    pub pulls: usize,
+    pub pushes: usize,
+    pub shots: usize,
+    pub kills: usize,
+    pub bombs: usize,
+    pub teleports: usize,
+    pub deaths: usize,
+    /// restarts asked for by the player, with `reload_level` or self-destruct
+    pub retries: usize,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+    /// Stats for another attempt of the same level, only deaths and retries are kept
+    pub fn next_attempt(&self) -> Stats {
+        Stats {
+            deaths: self.deaths,
+            retries: self.retries,
+            ..Stats::default()
+        }
+    }
+}
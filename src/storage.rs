@@ -0,0 +1,80 @@
+use log::log;
+
+pub trait Storage {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&mut self, key: &str, value: &str);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    storage: Option<web_sys::Storage>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    pub fn new() -> LocalStorage {
+        LocalStorage {
+            storage: web_sys::window().and_then(|window| window.local_storage().ok().flatten()),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.storage
+            .as_ref()
+            .and_then(|storage| storage.get_item(key).ok().flatten())
+    }
+    fn set(&mut self, key: &str, value: &str) {
+        if let Some(storage) = &self.storage {
+            if storage.set_item(key, value).is_err() {
+                log!("cannot store {} in localStorage", key);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    pub fn new<P: Into<std::path::PathBuf>>(dir: P) -> FileStorage {
+        FileStorage { dir: dir.into() }
+    }
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        let file_name: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .collect();
+        self.dir.join(format!("{}.txt", file_name))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+    fn set(&mut self, key: &str, value: &str) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .and_then(|_| std::fs::write(self.path(key), value));
+        if let Err(err) = result {
+            log!("cannot store {} in {:?}: {}", key, self.dir, err);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn default_storage() -> Box<dyn Storage> {
+    Box::new(LocalStorage::new())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn default_storage() -> Box<dyn Storage> {
+    let dir = std::env::var("ROBBO_DATA_DIR").unwrap_or_else(|_| String::from("robbo-data"));
+    Box::new(FileStorage::new(dir))
+}
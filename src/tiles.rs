@@ -1,4 +1,6 @@
 use consts;
+use log::log;
+use rules::RuleSet;
 use types::{Direction, Flags, Kind, Position};
 use utils::dest_coords;
 
@@ -24,6 +26,9 @@ impl Tile {
     pub fn key() -> Tile {
         Tile::new(Kind::Key, consts::COLLECTABLE, 42)
     }
+    pub fn empty() -> Tile {
+        EMPTY
+    }
     pub fn ground() -> Tile {
         Tile::new(Kind::Ground, consts::DESTROYABLE, 77)
     }
@@ -48,6 +53,12 @@ impl Tile {
     pub fn is_empty(&self) -> bool {
         self.kind == Kind::Empty
     }
+    pub fn is_enemy(&self) -> bool {
+        matches!(
+            self.kind,
+            Kind::Butterfly | Kind::Bear | Kind::BlackBear | Kind::Bird
+        )
+    }
     pub fn get_kind(&self) -> Kind {
         self.kind
     }
@@ -62,9 +73,10 @@ const EMPTY: Tile = Tile {
     tile: 95,
 };
 
+/// What lies beyond the board, it can't be destroyed even by bombs
 const WALL: Tile = Tile {
     kind: Kind::Wall,
-    flags: 0,
+    flags: consts::UNDESTROYABLE,
     tile: 0,
 };
 
@@ -75,10 +87,11 @@ pub struct Tiles {
     pub frame_cnt: usize,
     pub robbo_pos: Option<Position>,
     pub magnetic_force_dir: Option<Direction>,
+    pub rules: RuleSet,
 }
 
 impl Tiles {
-    pub fn new(width: i32, height: i32) -> Tiles {
+    pub fn new(width: i32, height: i32, rules: RuleSet) -> Tiles {
         let mut tiles = Vec::with_capacity((width * height) as usize);
         for _ in 0..width * height {
             tiles.push(EMPTY)
@@ -90,22 +103,29 @@ impl Tiles {
             frame_cnt: 0,
             robbo_pos: Some((0, 0)),
             magnetic_force_dir: None,
+            rules,
         }
     }
+    /// Puts the tile at `pos`, positions outside of the board are ignored
     pub fn put(&mut self, pos: Position, tile: Tile) {
+        if pos.0 < 0 || pos.0 >= self.width || pos.1 < 0 || pos.1 >= self.height {
+            log!("tile outside of the board: {:?}", pos);
+            return;
+        }
         self.tiles[(pos.0 + pos.1 * self.width) as usize] = tile;
     }
     pub fn put_empty(&mut self, pos: Position) {
         self.put(pos, EMPTY)
     }
+    /// Tile at `pos`, `None` outside of the board
     pub fn get(&self, pos: Position) -> Option<&Tile> {
+        if pos.0 < 0 || pos.0 >= self.width || pos.1 < 0 || pos.1 >= self.height {
+            return None;
+        }
         self.tiles.get((pos.0 + pos.1 * self.width) as usize)
     }
     pub fn get_or_wall(&self, pos: Position) -> Tile {
-        if pos.0 < 0 || pos.0 >= self.width || pos.1 < 0 || pos.1 >= self.height {
-            return WALL;
-        }
-        *self.tiles.get((pos.0 + pos.1 * self.width) as usize).unwrap_or(&WALL)
+        *self.get(pos).unwrap_or(&WALL)
     }
     pub fn get_kind(&self, pos: Position) -> Kind {
         self.get(pos).map(|v| v.kind).unwrap_or(Kind::Wall)
@@ -113,9 +133,9 @@ impl Tiles {
     pub fn is_empty(&self, pos: Position) -> bool {
         self.get_kind(pos) == Kind::Empty
     }
-    pub fn get_neighbours(&self, pos: Position) -> Neighbourhood {
-        return Neighbourhood::new(
-            &self,
+    pub fn get_neighbours(&self, pos: Position) -> Neighbourhood<'_> {
+        Neighbourhood::new(
+            self,
             pos,
         )
     }
@@ -132,8 +152,8 @@ impl<'tiles> Neighbourhood<'tiles> {
     pub fn new(tiles: &'tiles Tiles, pos: Position) -> Neighbourhood<'tiles> {
         let robbo_dir = tiles.robbo_pos.map(|(robbo_x, robbo_y)| {
             (
-                (robbo_x as i32) - (pos.0 as i32),
-                (robbo_y as i32) - (pos.1 as i32),
+                robbo_x - pos.0,
+                robbo_y - pos.1,
             )
         });
 
@@ -153,10 +173,10 @@ impl<'tiles> Neighbourhood<'tiles> {
     pub fn get_kind(&self, direction: Direction) -> Kind {
         self.get(direction).kind
     }
-    pub fn is_deadly(&self) -> bool {
+    pub fn get_deadly(&self) -> Option<(Kind, Direction)> {
         consts::ALL_DIRS
             .iter()
-            .map(|i| self.get(*i))
-            .any(|t| t.is_deadly())
+            .find(|dir| self.get(**dir).is_deadly())
+            .map(|dir| (self.get(*dir).kind, *dir))
     }
 }
@@ -1,9 +1,11 @@
+use death::DeathCause;
+use events::GameEvent;
 use sound::Sound;
 pub type Position = (i32, i32);
 pub type Direction = (i32, i32);
 pub type Flags = u16;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Kind {
     Empty,
     Wall,
@@ -47,12 +49,15 @@ pub enum Action {
     SpawnRobbo,
     SpawnRandomItem,
     TeleportRobbo(u16, u16, Direction),
-    NextLevel,
+    EnterCapsule(Position),
     SmallExplosion,
-    KillRobbo,
+    KillRobbo(DeathCause),
     ExplodeAll,
     ForceField,
     PlaySound(Sound),
+    /// sound of an object acting every tick, heard once per tick however many objects play it
+    PlayAmbientSound(Sound),
+    Emit(GameEvent),
 }
 pub struct Actions {
     actions: Vec<Action>
@@ -60,7 +65,7 @@ pub struct Actions {
 
 impl Actions {
     pub fn new(actions: &[Action]) -> Actions {
-        Actions {actions: actions.iter().cloned().collect()}
+        Actions {actions: actions.to_vec()}
     }
     pub fn single(action: Action) -> Actions {
         Actions {actions: vec![action]}
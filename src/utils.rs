@@ -22,9 +22,9 @@ pub fn reverse_direction((kx, ky): Direction) -> Direction {
     (-kx, -ky)
 }
 
+/// Direction from its level data index, indexes above 3 wrap around
 pub fn direction_by_index(index: usize) -> Direction {
-    assert!(index < 4);
-    consts::ALL_DIRS[index]
+    consts::ALL_DIRS[index % consts::ALL_DIRS.len()]
 }
 
 pub fn direction_to_index(dir: Direction) -> usize {
@@ -52,3 +52,10 @@ pub fn dest_coords((x, y): Position, (kx, ky): Direction) -> Position {
 pub fn modulo(n: i32, k: i32) -> i32 {
     ((n % k) + k) % k
 }
+
+/// FNV-1a hash, stable across runs and platforms
+pub fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
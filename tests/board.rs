@@ -0,0 +1,46 @@
+//! Board consistency checks, `Board::check_invariants` runs after every tick in debug builds
+#![cfg(debug_assertions)]
+extern crate rust_robbo;
+
+mod support;
+
+const MAP: &str = "
+    OOOOOO
+    OR#.TO
+    OOOOOO
+    ";
+
+#[test]
+fn consistent_board_passes() {
+    let mut board = support::board(MAP, "");
+    board.robbo_move_or_shot((1, 0), false);
+    for _ in 0..4 {
+        board.tick();
+    }
+    board.check_invariants();
+}
+
+#[test]
+#[should_panic(expected = "(2, 1): tile Empty, item Some(ABox)")]
+fn tile_without_item_is_reported() {
+    let mut board = support::board(MAP, "");
+    board.tiles.put_empty((2, 1));
+    board.check_invariants();
+}
+
+#[test]
+#[should_panic(expected = "(2, 1): Robbo tile, Robbo is at (1, 1)")]
+fn stray_robbo_tile_is_reported() {
+    let mut board = support::board(MAP, "");
+    board.items.remove((2, 1));
+    board.tiles.put((2, 1), board.tiles.get_or_wall((1, 1)));
+    board.check_invariants();
+}
+
+#[test]
+#[should_panic(expected = "capsule needs 2 screws, only 1 placed")]
+fn unreachable_screw_count_is_reported() {
+    let mut board = support::board(MAP, "");
+    board.missing_screws = 2;
+    board.check_invariants();
+}
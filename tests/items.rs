@@ -0,0 +1,787 @@
+//! Scenario tests of item behavior, one or more per `Item` impl in `items/`
+extern crate rust_robbo;
+
+#[macro_use]
+mod support;
+
+use rust_robbo::engine::{Board, GameEvent, LevelSet, Registry, RuleSet, Sound};
+use std::rc::Rc;
+
+#[test]
+fn robbo_collects_ammo_screws_and_keys() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR'T%.O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 4,
+        "
+        OOOOOOO
+        O....RO
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn box_is_pushed_one_step_at_a_time() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR#...O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 2,
+        "
+        OOOOOOO
+        O..R#.O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn push_box_slides_until_it_hits_something() {
+    assert_board!(
+        "
+        OOOOOOOO
+        OR~....O
+        OOOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 1,
+        "
+        OOOOOOOO
+        O.R~...O
+        OOOOOOOO
+        "
+    );
+    assert_board!(
+        "
+        OOOOOOOO
+        OR~...#O
+        OOOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 4,
+        "
+        OOOOOOOO
+        O...R~#O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn bear_turns_left_whenever_it_can() {
+    assert_board!(
+        "
+        OOOOOO
+        OR.OOO
+        OOOOOO
+        O....O
+        O....O
+        O.@..O
+        OOOOOO
+        ",
+        ticks = 2,
+        "
+        OOOOOO
+        OR.OOO
+        OOOOOO
+        O....O
+        O@...O
+        O....O
+        OOOOOO
+        "
+    );
+}
+
+#[test]
+fn black_bear_turns_right_whenever_it_can() {
+    assert_board!(
+        "
+        OOOOOO
+        OR.OOO
+        OOOOOO
+        O....O
+        O....O
+        O.*..O
+        OOOOOO
+        ",
+        ticks = 4,
+        "
+        OOOOOO
+        OR.OOO
+        OOOOOO
+        O....O
+        O...*O
+        O....O
+        OOOOOO
+        "
+    );
+}
+
+#[test]
+fn bird_flies_back_and_forth() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR....O
+        OOOOOOO
+        O.^...O
+        OOOOOOO
+        ",
+        ticks = 5,
+        "
+        OOOOOOO
+        OR....O
+        OOOOOOO
+        O...^.O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn butterfly_flies_towards_robbo() {
+    assert_board!(
+        "
+        OOOOOOOO
+        OR.....O
+        O......O
+        O......O
+        O.....VO
+        OOOOOOOO
+        ",
+        ticks = 5,
+        "
+        OOOOOOOO
+        OR..V..O
+        O......O
+        O......O
+        O......O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn gun_bullet_explodes_on_a_wall() {
+    let before = "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}.....O
+        OOOOOOOO
+        ";
+    let additional = "1.3.}.0.0.0.0.0.0";
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 13,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}..:..O
+        OOOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 16,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}....xO
+        OOOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 19,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}.....O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn laser_goes_back_to_the_gun() {
+    let before = "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}....#O
+        OOOOOOOO
+        ";
+    let additional = "1.3.}.0.0.1.0.0.0";
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 14,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}+++:#O
+        OOOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 19,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}x...#O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn blaster_burns_through_ground() {
+    assert_board!(
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}.H..#O
+        OOOOOOOO
+        ",
+        additional = "1.3.}.0.0.2.0.0.0",
+        ticks = 21,
+        "
+        OOOOOOOO
+        OR.....O
+        OOOOOOOO
+        O}....#O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn bombs_explode_in_a_chain() {
+    let before = "
+        OOOOOOOO
+        OR'OOOOO
+        O......O
+        O.b....O
+        O.bb...O
+        O......O
+        OOOOOOOO
+        ";
+    assert_board!(
+        before,
+        walk = (1, 0),
+        shoot = (0, 1),
+        ticks = 4,
+        "
+        OOOOOOOO
+        O.ROOOOO
+        Oxxx...O
+        Oxxxx..O
+        Oxx.x..O
+        Oxxxx..O
+        OOOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        walk = (1, 0),
+        shoot = (0, 1),
+        ticks = 7,
+        "
+        OOOOOOOO
+        O.ROOOOO
+        O......O
+        O......O
+        O......O
+        O......O
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn question_mark_turns_into_a_random_object() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR'.?.O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        shoot = (1, 0),
+        ticks = 6,
+        "
+        OOOOOOO
+        O..R'.O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn door_opens_with_a_key() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR%D..O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 2,
+        "
+        OOOOOOO
+        O.R...O
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        "
+        OOOOOOO
+        OR.D..O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 3,
+        "
+        OOOOOOO
+        O.RD..O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn teleport_moves_robbo_to_the_next_teleport_of_the_group() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR&...O
+        OOOOOOO
+        O..&..O
+        OOOOOOO
+        ",
+        additional = "
+        2.1.&.1.0
+        3.3.&.1.1
+        ",
+        walk = (1, 0),
+        ticks = 4,
+        "
+        OOOOOOO
+        O.&...O
+        OOOOOOO
+        O..&R.O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn capsule_is_pushed_until_repaired_then_entered() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR!..TO
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 3,
+        "
+        OOOOOOO
+        O..R!TO
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        "
+        OOOOOOO
+        OR..!.O
+        OOOOOOO
+        ",
+        walk = (1, 0),
+        ticks = 4,
+        "
+        OOOOOOO
+        O...!.O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn magnet_pulls_robbo_in_and_kills_robbo() {
+    let before = "
+        OOOOOOO
+        OR...MO
+        OOOOOOO
+        ";
+    assert_board!(
+        before,
+        additional = "5.1.M.2",
+        ticks = 3,
+        "
+        OOOOOOO
+        O...RMO
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = "5.1.M.2",
+        ticks = 4,
+        "
+        OOOOOOO
+        O...xMO
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = "5.1.M.0",
+        ticks = 4,
+        "
+        OOOOOOO
+        OR...MO
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn force_fields_move_east_and_wrap_around() {
+    let before = "
+        OOOOOOO
+        OR....O
+        O.==.=O
+        OOOOOOO
+        ";
+    let additional = "
+        2.2.=.0
+        3.2.=.0
+        5.2.=.0
+        ";
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 1,
+        "
+        OOOOOOO
+        OR....O
+        O=.==.O
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 5,
+        "
+        OOOOOOO
+        OR....O
+        O.==.=O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn force_fields_move_west() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR....O
+        O==.=.O
+        OOOOOOO
+        ",
+        additional = "
+        1.2.=.2
+        2.2.=.2
+        4.2.=.2
+        ",
+        ticks = 1,
+        "
+        OOOOOOO
+        OR....O
+        O=.=.=O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn force_fields_are_contained_by_walls_and_level_edges() {
+    assert_board!(
+        "
+        OOOOOOOO
+        OR.....O
+        ..=O..=.
+        OOOOOOOO
+        ",
+        additional = "
+        2.2.=.0
+        6.2.=.0
+        ",
+        ticks = 1,
+        "
+        OOOOOOOO
+        OR.....O
+        =..O...=
+        OOOOOOOO
+        "
+    );
+}
+
+#[test]
+fn force_field_consumes_everything_in_its_way() {
+    assert_board!(
+        "
+        OOOOOOO
+        OR....O
+        O=#'b!O
+        OOOOOOO
+        ",
+        additional = "1.2.=.0",
+        ticks = 4,
+        "
+        OOOOOOO
+        OR....O
+        O....=O
+        OOOOOOO
+        "
+    );
+}
+
+#[test]
+fn force_field_kills_robbo_in_its_way() {
+    let mut board = support::board(
+        "
+        OOOOOO
+        O=R..O
+        OOOOOO
+        ",
+        "1.1.=.0",
+    );
+    board.tick();
+    assert_eq!(support::normalize(&board.to_string()), ["OOOOOO", "O.=..O", "OOOOOO"]);
+    assert_eq!(
+        board.get_death().map(|death| death.to_string()),
+        Some(String::from("Robbo at 2.1 was hit by ForceField at 1.1"))
+    );
+}
+
+fn sounds(events: &[GameEvent], sound: Sound) -> usize {
+    events
+        .iter()
+        .filter(|event| match event {
+            GameEvent::Sound(s, _) => *s == sound,
+            _ => false,
+        })
+        .count()
+}
+
+#[test]
+fn birds_and_force_fields_are_heard_once_per_tick() {
+    let mut board = support::board(
+        "
+        OOOOOOO
+        OR....O
+        O^.^..O
+        O==.=.O
+        O=O=..O
+        OOOOOOO
+        ",
+        "",
+    );
+    for _ in 0..3 {
+        board.take_events();
+        board.tick();
+        let events = board.take_events();
+        assert_eq!(sounds(&events, Sound::Bird), 1);
+        assert_eq!(sounds(&events, Sound::ForceField), 1);
+    }
+}
+
+#[test]
+fn magnet_pull_is_heard_when_it_starts() {
+    let mut board = support::board(
+        "
+        OOOOOOO
+        OR...MO
+        OOOOOOO
+        ",
+        "5.1.M.2",
+    );
+    board.take_events();
+    board.tick();
+    assert_eq!(sounds(&board.take_events(), Sound::Magnet), 1);
+    board.tick();
+    assert_eq!(sounds(&board.take_events(), Sound::Magnet), 0);
+}
+
+#[test]
+fn force_fields_moving_the_other_way_bound_the_segment() {
+    let before = "
+        OOOOOOO
+        OR....O
+        O=.=..O
+        OOOOOOO
+        ";
+    let additional = "
+        1.2.=.0
+        3.2.=.2
+        ";
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 1,
+        "
+        OOOOOOO
+        OR....O
+        O.=..=O
+        OOOOOOO
+        "
+    );
+    assert_board!(
+        before,
+        additional = additional,
+        ticks = 2,
+        "
+        OOOOOOO
+        OR....O
+        O..==.O
+        OOOOOOO
+        "
+    );
+    assert_board!(before, additional = additional, ticks = 3, before);
+}
+
+#[test]
+fn magnet_pulls_are_not_player_moves() {
+    let mut board = support::board(
+        "
+        OOOOOOO
+        OR...MO
+        OOOOOOO
+        ",
+        "5.1.M.2",
+    );
+    for _ in 0..3 {
+        board.tick();
+    }
+    assert_eq!(board.stats.steps, 3);
+    assert_eq!(board.stats.pulls, 3);
+    assert_eq!(board.get_run_record().moves, 0);
+}
+
+/// Robbo walks east into a teleport whose partner has the east cell blocked
+/// and free cells to the north and south
+fn blocked_teleport(rules: &str) -> Board {
+    let map = "
+        OOOOO
+        OR&OO
+        OO.OO
+        OO&OO
+        OO.OO
+        OOOOO
+        ";
+    let data = support::pack(map, "2.1.&.1.0\n2.3.&.1.1");
+    let data = data.replacen("[level]", &format!("[rules]\n{}\n[level]", rules), 1);
+    let level_set = LevelSet::parse(&data).unwrap();
+    assert_eq!(level_set.rules, RuleSet::by_name(rules).unwrap());
+    let registry = Rc::new(Registry::default());
+    let mut board = Board::from(&level_set.levels[0], 0, level_set.rules, &registry);
+    board.spawn_robbo();
+    board.robbo_move_or_shot((1, 0), false);
+    for _ in 0..4 {
+        board.tick();
+    }
+    board
+}
+
+#[test]
+fn teleport_search_direction_depends_on_the_rules() {
+    let gnu_robbo = support::normalize(&blocked_teleport("gnu_robbo").to_string());
+    assert_eq!(gnu_robbo[2], "OOROO");
+    let atari = support::normalize(&blocked_teleport("atari").to_string());
+    assert_eq!(atari[2], "OO.OO");
+    assert_eq!(atari[4], "OOROO");
+}
+
+#[test]
+fn teleport_without_a_free_cell_is_not_counted() {
+    let mut board = support::board(
+        "
+        OOOOO
+        OR&OO
+        OOOOO
+        OO&OO
+        OOOOO
+        ",
+        "
+        2.1.&.1.0
+        2.3.&.1.1
+        ",
+    );
+    board.robbo_move_or_shot((1, 0), false);
+    for _ in 0..4 {
+        board.tick();
+    }
+    assert_eq!(board.stats.teleports, 0);
+
+    let mut board = support::board(
+        "
+        OOOOO
+        OR&OO
+        OOOOO
+        OO&.O
+        OOOOO
+        ",
+        "
+        2.1.&.1.0
+        2.3.&.1.1
+        ",
+    );
+    board.robbo_move_or_shot((1, 0), false);
+    board.tick();
+    assert_eq!(board.stats.teleports, 1);
+}
+
+#[test]
+fn level_timer_starts_when_robbo_appears() {
+    let mut board = support::spawning_board(
+        "
+        OOOO
+        OR.O
+        OOOO
+        ",
+        "",
+    );
+    let mut spawn_ticks = 0;
+    while board.robbo.is_hidden {
+        board.tick();
+        spawn_ticks += 1;
+    }
+    assert!(spawn_ticks > 1);
+    assert_eq!(board.stats.ticks, 0);
+    board.tick();
+    assert_eq!(board.stats.ticks, 1);
+}
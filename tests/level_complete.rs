@@ -0,0 +1,156 @@
+//! Robbo entering the capsule, the capsule taking off and moving on to the next level
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::engine::{Board, LevelState};
+use rust_robbo::Universe;
+
+const MAP: &str = "
+    OOOOO
+    O.R!O
+    OOOOO
+    ";
+
+const NEXT_MAP: &str = "
+    OOOOO
+    OR!.O
+    OOOOO
+    ";
+
+/// Ticks until Robbo, walking east, is inside the repaired capsule
+fn board_the_capsule(board: &mut Board) -> usize {
+    board.robbo_move_or_shot((1, 0), false);
+    let mut ticks = 0;
+    while board.get_level_state() == LevelState::Playing {
+        board.tick();
+        ticks += 1;
+        assert!(ticks < 10, "Robbo never entered the capsule");
+    }
+    ticks
+}
+
+#[test]
+fn robbo_disappears_into_the_capsule_which_takes_off() {
+    let mut board = support::board(MAP, "");
+    board_the_capsule(&mut board);
+    assert_eq!(board.get_level_state(), LevelState::TakingOff);
+    assert!(board.robbo.is_hidden);
+    assert_eq!(support::normalize(&board.to_string()), ["OOOOO", "O..!O", "OOOOO"]);
+    for _ in 0..board.tiles.rules.level_complete_ticks {
+        board.tick();
+    }
+    assert_eq!(board.get_level_state(), LevelState::LevelComplete);
+    assert_eq!(support::normalize(&board.to_string()), ["OOOOO", "O...O", "OOOOO"]);
+}
+
+#[test]
+fn level_is_complete_after_the_configured_delay() {
+    let mut board = support::board(MAP, "");
+    board.tiles.rules.level_complete_ticks = 3;
+    board_the_capsule(&mut board);
+    board.tick();
+    board.tick();
+    assert_eq!(board.get_level_state(), LevelState::TakingOff);
+    board.tick();
+    assert_eq!(board.get_level_state(), LevelState::LevelComplete);
+}
+
+#[test]
+fn take_off_neither_counts_towards_the_run_nor_kills_hidden_robbo() {
+    let mut board = support::board(MAP, "");
+    let ticks = board_the_capsule(&mut board);
+    for _ in 0..50 {
+        board.tick();
+    }
+    assert_eq!(board.stats.ticks, ticks);
+    assert_eq!(board.get_death(), None);
+    assert!(!board.is_robbo_killed());
+}
+
+fn universe() -> Universe {
+    let mut universe = support::universe(support::pack_of(&[MAP, NEXT_MAP]), 0);
+    universe.robbo_move(1, 0);
+    universe
+}
+
+/// Ticks until the first level is complete
+fn play_first_level(universe: &mut Universe) {
+    for _ in 0..100 {
+        universe.tick();
+        if universe.get_level_state() == LevelState::LevelComplete {
+            return;
+        }
+    }
+    panic!("level not completed");
+}
+
+#[test]
+fn universe_loads_the_next_level_after_the_take_off() {
+    let mut universe = universe();
+    play_first_level(&mut universe);
+    assert_eq!(universe.get_current_level(), 0);
+    universe.tick();
+    assert_eq!(universe.get_current_level(), 1);
+    assert_eq!(universe.get_level_state(), LevelState::Playing);
+}
+
+#[test]
+fn universe_waits_on_level_complete_when_paused() {
+    let mut universe = universe();
+    universe.set_pause_on_level_complete(true);
+    play_first_level(&mut universe);
+    for _ in 0..10 {
+        universe.tick();
+    }
+    assert_eq!(universe.get_current_level(), 0);
+    assert_eq!(universe.get_level_state(), LevelState::LevelComplete);
+    assert!(universe.get_stats().ticks > 0);
+    universe.load_next_level();
+    assert_eq!(universe.get_current_level(), 1);
+    assert_eq!(universe.get_level_state(), LevelState::Playing);
+}
+
+/// Ticks from Robbo entering the capsule until the level is complete
+fn take_off_ticks(universe: &mut Universe) -> usize {
+    universe.set_pause_on_level_complete(true);
+    let mut ticks = 0;
+    for _ in 0..100 {
+        match universe.get_level_state() {
+            LevelState::LevelComplete => return ticks,
+            LevelState::TakingOff => ticks += 1,
+            _ => {}
+        }
+        universe.tick();
+    }
+    panic!("level not completed");
+}
+
+#[test]
+fn universe_overrides_the_take_off_duration() {
+    let default = take_off_ticks(&mut universe());
+    let mut universe = universe();
+    universe.set_level_complete_ticks(default + 5);
+    assert_eq!(take_off_ticks(&mut universe), default + 5);
+
+    universe.load_next_level();
+    universe.robbo_move(1, 0);
+    assert_eq!(take_off_ticks(&mut universe), default + 5);
+}
+
+#[test]
+fn universe_keeps_the_score_of_completed_levels() {
+    let mut universe = support::universe(support::pack_of(&["OOOOO\nOR'!O\nOOOOO", NEXT_MAP]), 0);
+    universe.robbo_move(1, 0);
+    for _ in 0..20 {
+        universe.tick();
+    }
+    let score = universe.get_score();
+    assert!(score > 0);
+    universe.robbo_move(1, 0);
+    play_first_level(&mut universe);
+    universe.tick();
+    assert_eq!(universe.get_current_level(), 1);
+    assert_eq!(universe.get_score(), score);
+    assert_eq!(universe.get_hud().score, score);
+}
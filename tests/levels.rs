@@ -0,0 +1,146 @@
+//! Golden master tests: every bundled level is played with a scripted input
+//! and the final tile grid and HUD are compared to `tests/snapshots/<pack>/<level>.txt`.
+//!
+//! Run with `UPDATE_SNAPSHOTS=1 cargo test --test levels` to rewrite the snapshots
+//! after an intended behavior change, and review the diff.
+//!
+//! A level that panics fails the test, unless it is listed in `KNOWN_PANICS`.
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::Universe;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+
+const TICKS: usize = 400;
+
+/// Levels (pack, 1-based level number) known to panic, they have no snapshot.
+/// A listed level that stops panicking fails the test too, so the list stays current
+const KNOWN_PANICS: &[(&str, usize)] = &[];
+const DIRS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+fn snapshot_path(pack: &str, level: usize) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(pack)
+        .join(format!("{:02}.txt", level + 1))
+}
+
+/// Walks in circles, changing direction every 13 ticks and shooting every 29 ticks
+fn play(universe: &mut Universe) {
+    for tick in 0..TICKS {
+        if tick % 13 == 0 {
+            let (kx, ky) = DIRS[(tick / 13) % 4];
+            universe.robbo_move(kx, ky);
+        }
+        if tick % 29 == 0 {
+            let (kx, ky) = DIRS[(tick / 29) % 4];
+            universe.robbo_shot(kx, ky);
+        }
+        universe.tick();
+    }
+}
+
+fn render(universe: &Universe) -> String {
+    let mut out = format!(
+        "level: {}\nticks: {}\n{}\n",
+        universe.get_current_level() + 1,
+        TICKS,
+        universe.get_inventory()
+    );
+    for y in 0..universe.get_board_height() {
+        let row: Vec<String> = (0..universe.get_board_width())
+            .map(|x| format!("{:3}", universe.get_tile(x, y)))
+            .collect();
+        out.push_str(row.join("").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Rendered final state of the level, or the panic message
+fn snapshot(data: &str, level: usize) -> Result<String, String> {
+    let data = String::from(data);
+    panic::catch_unwind(move || {
+        let mut universe = support::universe(data, level);
+        play(&mut universe);
+        render(&universe)
+    })
+    .map_err(|err| {
+        err.downcast_ref::<String>()
+            .cloned()
+            .or_else(|| err.downcast_ref::<&str>().map(|s| String::from(*s)))
+            .unwrap_or_default()
+    })
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let line = expected
+        .lines()
+        .zip(actual.lines())
+        .position(|(e, a)| e != a)
+        .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+    format!(
+        "line {}:\n  expected: {}\n  actual:   {}",
+        line + 1,
+        expected.lines().nth(line).unwrap_or("<eof>"),
+        actual.lines().nth(line).unwrap_or("<eof>")
+    )
+}
+
+fn check_pack(pack: &str) {
+    let path = format!("{}/www/data/levels/{}.txt", env!("CARGO_MANIFEST_DIR"), pack);
+    let data = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path, err));
+    let level_count = data.lines().filter(|line| *line == "[level]").count();
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+
+    let mut failures = Vec::new();
+    for level in 0..level_count {
+        let path = snapshot_path(pack, level);
+        let known_panic = KNOWN_PANICS.contains(&(pack, level + 1));
+        let actual = match (snapshot(&data, level), known_panic) {
+            (Ok(actual), false) => actual,
+            (Err(_), true) => continue,
+            (Ok(_), true) => {
+                failures.push(format!("{}: listed in KNOWN_PANICS but did not panic", path.display()));
+                continue;
+            }
+            (Err(message), false) => {
+                failures.push(format!("{}: panic: {}", path.display(), message));
+                continue;
+            }
+        };
+        if update {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        match fs::read_to_string(&path) {
+            Ok(expected) if expected == actual => (),
+            Ok(expected) => failures.push(format!(
+                "{}: {}",
+                path.display(),
+                first_difference(&expected, &actual)
+            )),
+            Err(err) => failures.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} level(s) failed, run with UPDATE_SNAPSHOTS=1 to accept snapshot differences:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn original_levels() {
+    check_pack("original");
+}
+
+#[test]
+fn forever_levels() {
+    check_pack("forever");
+}
@@ -0,0 +1,77 @@
+//! Level pack parsing: errors are returned with their line, bad geometry is clamped
+extern crate rust_robbo;
+
+use rust_robbo::engine::LevelSet;
+
+/// Pack with a single level of the given `[size]`, `[data]` and `[additional]` lines
+fn pack(size: &str, data: &[&str], additional: &[&str]) -> String {
+    format!(
+        "[name]\nscenario\n[level]\n1\n[size]\n{}\n[data]\n{}\n[additional]\n{}\n{}\n[end]\n",
+        size,
+        data.join("\n"),
+        additional.len(),
+        additional.join("\n")
+    )
+}
+
+fn error(data: &str) -> String {
+    match LevelSet::parse(data) {
+        Ok(_) => panic!("pack parsed:\n{}", data),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn section_without_a_value_is_an_error() {
+    assert_eq!(error("[name]"), "line 1: missing value of [name]");
+    assert_eq!(error("[name]\nscenario\n[level]"), "line 3: missing value of [level]");
+}
+
+#[test]
+fn invalid_numbers_are_errors() {
+    assert_eq!(error("[name]\nscenario\n[level]\nfirst\n"), "line 4: invalid number: first");
+    assert_eq!(error(&pack("4.x", &[], &[])), "line 6: invalid number: x");
+    assert_eq!(error(&pack("4", &[], &[])), "line 6: invalid number: ");
+}
+
+#[test]
+fn level_size_must_be_positive_and_at_most_the_maximum() {
+    assert_eq!(error(&pack("0.3", &[], &[])), "line 6: invalid level size: 0.3");
+    assert_eq!(error(&pack("4.257", &[], &[])), "line 6: invalid level size: 4.257");
+    assert!(LevelSet::parse(&pack("256.256", &[], &[])).is_ok());
+}
+
+#[test]
+fn additional_data_must_be_inside_the_level() {
+    let data = ["OOOO", "OR.O", "OOOO"];
+    assert_eq!(
+        error(&pack("4.3", &data, &["4.1.M.2"])),
+        "line 13: additional data outside of the level: 4.1.M.2"
+    );
+    assert_eq!(
+        error(&pack("4.3", &data, &["1.3.M.2"])),
+        "line 13: additional data outside of the level: 1.3.M.2"
+    );
+    assert_eq!(error(&pack("4.3", &data, &["1.1"])), "line 13: invalid additional data: 1.1");
+    assert_eq!(error(&pack("4.3", &data, &["2.1..x"])), "line 13: invalid number: x");
+    let truncated = "[name]\nscenario\n[level]\n1\n[size]\n4.3\n[data]\nOOOO\nOR.O\nOOOO\n[additional]\n2\n2.1.M.2";
+    assert_eq!(error(truncated), "line 13: missing value of [additional]");
+}
+
+#[test]
+fn pack_needs_a_name_and_a_level() {
+    let level = pack("4.3", &["OOOO", "OR.O", "OOOO"], &[]);
+    let unnamed = level.replacen("[name]\nscenario\n", "", 1);
+    // reported at the end of the pack
+    assert_eq!(error(&unnamed), "line 13: missing [name]");
+    assert_eq!(error("[name]\nscenario\n"), "line 3: no levels");
+}
+
+#[test]
+fn level_data_is_clamped_to_its_size() {
+    let level_set = LevelSet::parse(&pack("4.3", &["OOOOOO", "OR"], &[])).unwrap();
+    assert_eq!(level_set.levels[0].tiles, ["OOOO", "OR..", "...."]);
+
+    let level_set = LevelSet::parse(&pack("2.1", &["OR", "OO", "OO"], &[])).unwrap();
+    assert_eq!(level_set.levels[0].tiles, ["OR"]);
+}
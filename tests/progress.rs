@@ -0,0 +1,161 @@
+//! Campaign progression: levels unlock in order, practice never unlocks anything
+//! and the progress of a pack survives restarting the game
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::engine::{Progress, Storage};
+use support::MemoryStorage;
+use rust_robbo::{PlayMode, Universe};
+
+const LEVEL: &str = "
+    OOOO
+    OR!O
+    OOOO
+    ";
+
+/// Three level pack of given name, saved data goes to a directory of this test run only
+fn pack(name: &str) -> String {
+    let dir = format!("{}/robbo-progress-{}", env!("CARGO_TARGET_TMPDIR"), std::process::id());
+    std::env::set_var("ROBBO_DATA_DIR", dir);
+    support::pack_of(&[LEVEL, LEVEL, LEVEL]).replacen("scenario", name, 1)
+}
+
+/// Universe playing a three level pack of its own
+fn universe(pack: &str, play_mode: PlayMode) -> Universe {
+    let mut universe = Universe::new(self::pack(pack), 0).unwrap();
+    universe.set_play_mode(play_mode);
+    universe
+}
+
+/// Walks Robbo into the capsule and waits for the next level
+fn complete_level(universe: &mut Universe) {
+    let level = universe.get_current_level();
+    universe.robbo_move(1, 0);
+    for _ in 0..100 {
+        universe.tick();
+        if universe.get_current_level() != level {
+            return;
+        }
+    }
+    panic!("level {} not completed", level + 1);
+}
+
+#[test]
+fn first_level_is_unlocked_and_each_completion_unlocks_the_next_one() {
+    let mut progress = Progress::default();
+    assert!(progress.is_unlocked(0));
+    assert!(!progress.is_unlocked(1));
+    assert_eq!(progress.last_unlocked(3), 0);
+
+    progress.completed.insert(0);
+    assert!(progress.is_unlocked(1));
+    assert!(!progress.is_unlocked(2));
+    assert_eq!(progress.last_unlocked(3), 1);
+
+    progress.completed.insert(2);
+    assert!(progress.is_unlocked(2));
+    assert_eq!(progress.last_unlocked(3), 2);
+}
+
+#[test]
+fn progress_is_saved_and_loaded_per_pack() {
+    let mut storage = MemoryStorage::default();
+    let progress = Progress {
+        current_level: 4,
+        completed: vec![0, 1, 3].into_iter().collect(),
+    };
+    progress.save(&mut storage, "original");
+    assert_eq!(Progress::load(&storage, "original"), progress);
+    assert_eq!(Progress::load(&storage, "forever"), Progress::default());
+}
+
+#[test]
+fn level_saved_under_the_legacy_key_is_migrated() {
+    let mut storage = MemoryStorage::default();
+    storage.set("current_level_original", "4");
+    let progress = Progress::load_and_migrate(&mut storage, "Original");
+    assert_eq!(progress.current_level, 4);
+    assert_eq!(Progress::load(&storage, "Original"), progress);
+    assert!(storage.get("progress_Original").is_some());
+
+    storage.set("current_level_original", "7");
+    assert_eq!(Progress::load_and_migrate(&mut storage, "Original").current_level, 4);
+}
+
+#[test]
+fn universe_restores_the_level_saved_under_the_legacy_key() {
+    let mut storage = MemoryStorage::default();
+    storage.set("current_level_legacy", "2");
+    let data = self::pack("Legacy");
+    let mut universe = Universe::with_storage(data, 0, Box::new(storage)).unwrap();
+    assert!(universe.select_pack(String::from("Legacy")));
+    assert_eq!(universe.get_current_level(), 2);
+}
+
+#[test]
+fn campaign_levels_unlock_one_by_one() {
+    let mut universe = universe("campaign", PlayMode::Campaign);
+    assert!(!universe.select_level(1));
+    assert!(!universe.is_level_unlocked(1));
+
+    complete_level(&mut universe);
+    assert_eq!(universe.get_current_level(), 1);
+    assert_eq!(universe.get_completed_levels(), [0]);
+    assert!(universe.is_level_unlocked(1));
+    assert!(!universe.is_level_unlocked(2));
+}
+
+#[test]
+fn practice_completions_do_not_unlock_campaign_levels() {
+    let mut universe = universe("practice", PlayMode::Practice);
+    assert!(universe.select_level(1));
+    complete_level(&mut universe);
+    assert_eq!(universe.get_current_level(), 2);
+    assert!(universe.get_completed_levels().is_empty());
+
+    universe.set_play_mode(PlayMode::Campaign);
+    assert!(!universe.is_level_unlocked(2));
+    assert_eq!(universe.get_current_level(), 0);
+}
+
+#[test]
+fn switching_to_practice_opens_every_level() {
+    let mut universe = universe("switching", PlayMode::Campaign);
+    assert!(!universe.is_level_unlocked(2));
+    universe.set_play_mode(PlayMode::Practice);
+    assert!(universe.select_level(2));
+}
+
+#[test]
+fn campaign_progress_survives_a_restart() {
+    let mut universe = universe("restored", PlayMode::Campaign);
+    complete_level(&mut universe);
+
+    let restarted = self::universe("restored", PlayMode::Campaign);
+    assert_eq!(restarted.get_completed_levels(), [0]);
+    assert!(restarted.is_level_unlocked(1));
+}
+
+#[test]
+fn campaign_never_starts_on_a_locked_level() {
+    universe("locked", PlayMode::Campaign);
+    let universe = Universe::new(pack("locked"), 2).unwrap();
+    assert_eq!(universe.get_play_mode(), PlayMode::Campaign);
+    assert_eq!(universe.get_current_level(), 0);
+
+    let universe = Universe::new(pack("unlocked"), 2).unwrap();
+    assert_eq!(universe.get_play_mode(), PlayMode::Practice);
+    assert_eq!(universe.get_current_level(), 2);
+}
+
+#[test]
+fn play_mode_is_saved_per_pack() {
+    let mut universe = universe("campaign_pack", PlayMode::Campaign);
+    universe.add_pack(pack("practice_pack")).unwrap();
+    assert!(universe.select_pack(String::from("practice_pack")));
+    assert_eq!(universe.get_play_mode(), PlayMode::Practice);
+
+    assert!(universe.select_pack(String::from("campaign_pack")));
+    assert_eq!(universe.get_play_mode(), PlayMode::Campaign);
+}
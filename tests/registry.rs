@@ -0,0 +1,43 @@
+//! Registries built outside the crate: objects with symbols and names of their own
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::engine::{Board, ItemDef, Kind, LevelSet, Registry, Tile};
+use std::rc::Rc;
+
+const MAP: &str = "
+    OOOOO
+    OR.XO
+    OOYOO
+    ";
+
+/// Default registry plus a wall `X` and a box `Y`, named at runtime
+fn registry() -> Registry {
+    let mut registry = Registry::default();
+    let (wall_name, box_name) = (String::from("brick"), String::from("crate"));
+    registry.register_tile('X', &wall_name, Tile::wall(90));
+    registry.register('Y', &box_name, ItemDef::new(Kind::ABox, vec![91, 92], |item, _| item.into()));
+    registry
+}
+
+#[test]
+fn levels_are_parsed_with_registered_symbols() {
+    let level_set = LevelSet::parse(&support::pack(MAP, "")).unwrap();
+    let mut board = Board::from(&level_set.levels[0], 0, level_set.rules, &Rc::new(registry()));
+    board.spawn_robbo();
+    assert_eq!(support::normalize(&board.to_string()), support::normalize(MAP));
+    assert_eq!((board.get_tile((3, 1)), board.get_tile((2, 2))), (90, 91));
+
+    let registry = registry();
+    assert_eq!(registry.logical_tiles("brick"), Some(&[90][..]));
+    assert_eq!(registry.logical_tiles("crate"), Some(&[91, 92][..]));
+}
+
+#[test]
+fn universe_reads_levels_with_the_registry_it_is_given() {
+    let mut universe = support::universe(support::pack(MAP, ""), 0);
+    assert_ne!(universe.get_tile(3, 1), 90);
+    universe.set_registry(registry());
+    assert_eq!((universe.get_tile(3, 1), universe.get_tile(2, 2)), (90, 91));
+}
@@ -0,0 +1,60 @@
+//! Replays are tied to the pack and the exact level data they were recorded on
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::engine::{Input, LevelSet, Registry, Replay, Storage};
+use rust_robbo::Universe;
+use std::rc::Rc;
+use support::MemoryStorage;
+
+const LEVEL: &str = "
+    OOOOO
+    OR.!O
+    OOOOO
+    ";
+
+fn replay(level_set: &LevelSet) -> Replay {
+    let level = &level_set.levels[0];
+    let mut replay = Replay::new(&level_set.name, 0, level.checksum(), 0);
+    replay.record(0, Input::Move((1, 0)));
+    replay
+}
+
+#[test]
+fn replay_keeps_pack_and_level_checksum() {
+    let level_set = LevelSet::parse(&support::pack(LEVEL, "")).unwrap();
+    let replay = replay(&level_set);
+    let parsed = Replay::parse(&replay.serialize()).unwrap();
+    assert_eq!(parsed, replay);
+    assert_eq!(parsed.pack, "scenario");
+}
+
+#[test]
+fn replay_plays_only_on_the_level_it_was_recorded_on() {
+    let registry = Rc::new(Registry::default());
+    let level_set = LevelSet::parse(&support::pack(LEVEL, "")).unwrap();
+    let replay = replay(&level_set);
+    let record = replay.play(&level_set.levels[0], level_set.rules, &registry);
+    assert_eq!(record.map(|record| record.moves), Some(1));
+
+    let changed = LevelSet::parse(&support::pack("OOOOO\nOR.!O\nO.OOO", "")).unwrap();
+    assert_eq!(replay.play(&changed.levels[0], changed.rules, &registry), None);
+}
+
+#[test]
+fn only_replays_linked_from_the_leaderboard_verify() {
+    let data = support::pack(LEVEL, "");
+    let level_set = LevelSet::parse(&data).unwrap();
+    let mut storage = MemoryStorage::default();
+    storage.set("unlinked", &replay(&level_set).serialize());
+    let mut universe = Universe::with_storage(data, 0, Box::new(storage)).unwrap();
+    assert!(!universe.verify_replay("unlinked"));
+
+    universe.robbo_move(1, 0);
+    for _ in 0..100 {
+        universe.tick();
+    }
+    let best_time = universe.get_best_time(0).expect("level not completed");
+    assert!(universe.verify_replay(&best_time.replay_key()));
+}
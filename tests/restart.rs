@@ -0,0 +1,152 @@
+//! Restarting the level with Esc, from every state Robbo may be in
+extern crate rust_robbo;
+
+mod support;
+
+use rust_robbo::engine::{Board, LevelState, Restart};
+
+const MAP: &str = "
+    OOOOOO
+    O.R..O
+    OOOOOO
+    ";
+
+fn death(board: &Board) -> Option<String> {
+    board.get_death().map(|death| death.to_string())
+}
+
+fn robbo_killed_events(board: &Board) -> usize {
+    board
+        .take_events()
+        .iter()
+        .filter(|event| format!("{:?}", event).starts_with("RobboKilled"))
+        .count()
+}
+
+#[test]
+fn robbo_explodes_and_the_board_asks_for_a_restart() {
+    let mut board = support::board(MAP, "");
+    board.request_restart();
+    assert_eq!(board.get_restart(), Restart::Requested);
+    board.tick();
+    assert_eq!(board.get_restart(), Restart::Exploding);
+    assert_eq!(death(&board), Some(String::from("Robbo at 2.1 self-destructed")));
+    assert_eq!(support::normalize(&board.to_string()), ["OOOOOO", "O.x..O", "OOOOOO"]);
+    for _ in 0..30 {
+        board.tick();
+    }
+    assert!(board.is_robbo_killed());
+}
+
+#[test]
+fn restart_during_spawn_waits_for_robbo() {
+    let mut board = support::spawning_board(MAP, "");
+    board.request_restart();
+    let mut ticks = 0;
+    while board.robbo.is_hidden {
+        board.tick();
+        assert_eq!(death(&board), None);
+        ticks += 1;
+        assert!(ticks < 20, "Robbo never spawned");
+    }
+    assert_eq!(board.get_restart(), Restart::Requested);
+    board.tick();
+    assert_eq!(death(&board), Some(String::from("Robbo at 2.1 self-destructed")));
+}
+
+#[test]
+fn restart_during_teleport_waits_for_robbo_at_the_destination() {
+    let mut board = support::board(
+        "
+        OOOOOOO
+        OR&...O
+        OOOOOOO
+        O..&..O
+        OOOOOOO
+        ",
+        "
+        2.1.&.1.0
+        3.3.&.1.1
+        ",
+    );
+    board.robbo_move_or_shot((1, 0), false);
+    board.tick();
+    board.robbo_move_or_shot((0, 0), false);
+    assert!(board.robbo.is_hidden);
+    board.request_restart();
+    while board.robbo.is_hidden {
+        board.tick();
+        assert_eq!(death(&board), None);
+    }
+    board.tick();
+    assert_eq!(death(&board), Some(String::from("Robbo at 4.3 self-destructed")));
+}
+
+#[test]
+fn restart_is_ignored_once_robbo_entered_the_capsule() {
+    let mut board = support::board(
+        "
+        OOOO
+        OR!O
+        OOOO
+        ",
+        "",
+    );
+    board.robbo_move_or_shot((1, 0), false);
+    for _ in 0..4 {
+        board.tick();
+    }
+    assert_eq!(board.get_level_state(), LevelState::TakingOff);
+    board.request_restart();
+    board.tick();
+    assert_eq!(board.get_restart(), Restart::Idle);
+    assert_eq!(death(&board), None);
+}
+
+#[test]
+fn restart_is_ignored_while_robbo_is_dying() {
+    let mut board = support::board(MAP, "");
+    board.request_restart();
+    board.tick();
+    board.request_restart();
+    board.tick();
+    board.request_restart();
+    for _ in 0..5 {
+        board.tick();
+    }
+    assert_eq!(robbo_killed_events(&board), 1);
+    assert_eq!(death(&board), Some(String::from("Robbo at 2.1 self-destructed")));
+}
+
+#[test]
+fn universe_reloads_the_level_after_the_explosion() {
+    let mut universe = support::universe(support::pack(MAP, ""), 0);
+    let lives = universe.get_lives();
+    universe.kill_robbo();
+    universe.kill_robbo();
+    for _ in 0..60 {
+        universe.tick();
+    }
+    let stats = universe.get_stats();
+    assert_eq!((stats.deaths, stats.retries), (1, 1));
+    assert_eq!(universe.get_lives(), lives - 1);
+    let death = universe.get_last_death().unwrap();
+    assert_eq!((format!("{:?}", death.cause), death.x, death.y), (String::from("SelfDestruct"), 2, 1));
+}
+
+#[test]
+fn only_restarts_asked_for_by_the_player_count_as_retries() {
+    let mut universe = support::universe(support::pack("OOOOOO\nO.R@.O\nOOOOOO", ""), 0);
+    for _ in 0..60 {
+        if universe.get_stats().deaths > 0 {
+            break;
+        }
+        universe.tick();
+    }
+    let stats = universe.get_stats();
+    assert_eq!((stats.deaths, stats.retries), (1, 0));
+
+    universe.reload_level();
+    let stats = universe.get_stats();
+    assert_eq!((stats.deaths, stats.retries), (1, 1));
+}
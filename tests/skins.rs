@@ -0,0 +1,51 @@
+//! Bundled skins map logical tiles by object names known to the registry
+extern crate rust_robbo;
+
+use rust_robbo::engine::{Registry, Skin};
+use std::fs;
+
+#[test]
+fn bundled_skins_name_registered_objects() {
+    let registry = Registry::default();
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/www/data/skins");
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path().join("skin.txt");
+        let data = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        let tiles = data
+            .lines()
+            .map(|line| line.trim())
+            .skip_while(|line| *line != "[tiles]")
+            .skip(1)
+            .take_while(|line| !line.starts_with('['))
+            .filter(|line| !line.is_empty());
+        for line in tiles {
+            let name = line.split('.').next().unwrap();
+            assert!(registry.logical_tiles(name).is_some(), "{}: unknown object {}", path.display(), name);
+        }
+    }
+}
+
+#[test]
+fn objects_are_named_after_their_symbols_and_sprites() {
+    let registry = Registry::default();
+    assert_eq!(registry.logical_tiles("gun"), Some(&[53, 54, 55, 56][..]));
+    assert_eq!(registry.logical_tiles("robbo"), Some(&[60, 61, 62, 63, 64, 65, 66, 67][..]));
+    assert_eq!(registry.logical_tiles("blast"), Some(&[84, 85, 86][..]));
+    assert_eq!(registry.logical_tiles("unknown"), None);
+}
+
+#[test]
+fn skins_name_objects_registered_by_forks() {
+    let mut registry = Registry::default();
+    registry.register_sprite("lamp", &[200, 201]);
+    let skin = Skin::parse("[tiles]\nlamp.7.8", &registry);
+    assert_eq!(skin.resolve(200, false), 7);
+    assert_eq!(skin.resolve(201, false), 8);
+}
+
+#[test]
+fn zero_columns_keep_the_default() {
+    let skin = Skin::parse("[columns]\n0", &Registry::default());
+    assert_eq!(skin.columns, Skin::default().columns);
+    assert_eq!(skin.atlas_y(13), skin.spacing + skin.border);
+}
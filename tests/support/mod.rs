@@ -0,0 +1,169 @@
+//! Scenario helpers: boards are written as ASCII maps of level symbols,
+//! see `Board`'s `Display` impl for symbols of objects created during the game.
+// not every test crate uses every helper
+#![allow(dead_code, unused_macros)]
+use rust_robbo::engine::{Board, LevelSet, Registry, Storage};
+use rust_robbo::Universe;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Map lines with the indentation and blank lines removed
+pub fn normalize(map: &str) -> Vec<String> {
+    map.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Storage kept in memory, so tests neither share nor leave saved data behind
+#[derive(Default)]
+pub struct MemoryStorage {
+    values: HashMap<String, String>,
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+    fn set(&mut self, key: &str, value: &str) {
+        self.values.insert(String::from(key), String::from(value));
+    }
+}
+
+/// Universe playing the pack with nothing saved yet
+pub fn universe(level_data: String, current_level: usize) -> Universe {
+    Universe::with_storage(level_data, current_level, Box::new(MemoryStorage::default())).unwrap()
+}
+
+/// Level pack named `scenario` made of `maps`, levels have no `[additional]` lines
+pub fn pack_of(maps: &[&str]) -> String {
+    let levels: Vec<String> = maps
+        .iter()
+        .enumerate()
+        .map(|(index, map)| level(index + 1, map, ""))
+        .collect();
+    format!("[name]\nscenario\n{}", levels.concat())
+}
+
+/// Level pack with a single level made of the map and `[additional]` lines
+/// (`x.y.symbol.params...`)
+pub fn pack(map: &str, additional: &str) -> String {
+    format!("[name]\nscenario\n{}", level(1, map, additional))
+}
+
+fn level(number: usize, map: &str, additional: &str) -> String {
+    let rows = normalize(map);
+    let additional = normalize(additional);
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    format!(
+        "[level]\n{}\n[size]\n{}.{}\n[data]\n{}\n[additional]\n{}\n{}\n[end]\n",
+        number,
+        width,
+        rows.len(),
+        rows.join("\n"),
+        additional.len(),
+        additional.join("\n")
+    )
+}
+
+/// Board as loaded, Robbo is still spawning
+pub fn spawning_board(map: &str, additional: &str) -> Board {
+    let level_set = LevelSet::parse(&pack(map, additional)).unwrap();
+    let registry = Rc::new(Registry::default());
+    Board::from(&level_set.levels[0], 0, level_set.rules, &registry)
+}
+
+/// Board with Robbo placed on the map already spawned; without Robbo the board
+/// explodes after `hidden_robbo_explode_ticks`.
+pub fn board(map: &str, additional: &str) -> Board {
+    let mut board = spawning_board(map, additional);
+    board.spawn_robbo();
+    board
+}
+
+/// Board with the input held for the whole scenario
+pub struct Scenario {
+    map: String,
+    additional: String,
+    walk: Option<(i32, i32)>,
+    shoot: Option<(i32, i32)>,
+    ticks: usize,
+}
+
+impl Scenario {
+    pub fn new(map: &str) -> Scenario {
+        Scenario {
+            map: String::from(map),
+            additional: String::new(),
+            walk: None,
+            shoot: None,
+            ticks: 0,
+        }
+    }
+
+    pub fn additional(self, additional: &str) -> Scenario {
+        Scenario {
+            additional: String::from(additional),
+            ..self
+        }
+    }
+
+    /// Robbo keeps walking in the direction
+    pub fn walk(self, dir: (i32, i32)) -> Scenario {
+        Scenario {
+            walk: Some(dir),
+            ..self
+        }
+    }
+
+    /// Robbo shoots once in the direction
+    pub fn shoot(self, dir: (i32, i32)) -> Scenario {
+        Scenario {
+            shoot: Some(dir),
+            ..self
+        }
+    }
+
+    pub fn ticks(self, ticks: usize) -> Scenario {
+        Scenario { ticks, ..self }
+    }
+
+    /// Plays the scenario and returns the printed board
+    pub fn run(&self) -> Vec<String> {
+        let mut board = board(&self.map, &self.additional);
+        if let Some(dir) = self.walk {
+            board.robbo_move_or_shot(dir, false);
+        }
+        if let Some(dir) = self.shoot {
+            board.robbo_move_or_shot(dir, true);
+        }
+        for _ in 0..self.ticks {
+            board.tick();
+        }
+        normalize(&board.to_string())
+    }
+}
+
+/// `assert_board!(before, ticks = n, after)`; `additional = "x.y.symbol.params..."`,
+/// `walk = (dx, dy)` and `shoot = (dx, dy)` may be given before `ticks`
+macro_rules! assert_board {
+    (@options $before:expr, $scenario:expr; ticks = $ticks:expr, $after:expr) => {{
+        let actual = $scenario.ticks($ticks).run();
+        let expected = support::normalize($after);
+        assert!(
+            actual == expected,
+            "board after {} ticks differs\nbefore:\n{}\nexpected:\n{}\nactual:\n{}\n",
+            $ticks,
+            support::normalize($before).join("\n"),
+            expected.join("\n"),
+            actual.join("\n")
+        );
+    }};
+    (@options $before:expr, $scenario:expr; $option:ident = $value:expr, $($rest:tt)+) => {
+        assert_board!(@options $before, $scenario.$option($value); $($rest)+)
+    };
+    ($before:expr, $($rest:tt)+) => {
+        assert_board!(@options $before, support::Scenario::new($before); $($rest)+)
+    };
+}